@@ -4,5 +4,7 @@ use rspectrum::RSSpectrum;
 fn main() {
     let mut emu = RSSpectrum::new();
     println!("Hello, RS Spectrum!");
-    emu.run();
+    if let Err(e) = emu.run() {
+        eprintln!("CPU stopped: {}", e);
+    }
 }
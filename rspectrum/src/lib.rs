@@ -1,27 +1,63 @@
 //! Provides an emulated ZX Spectrum.
-use rz80::Z80;
+use rz80::{bus::Ram, DecodeError, Z80};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
 
-const MEM_SIZE: usize = (16 + 48) * 1024;
+/// Frequency of the CPU clock in Hz.
+const CLOCK_HZ: u64 = 3_500_000;
+/// T-states in one 50 Hz video frame.
+const FRAME_CYCLES: u64 = 69_888;
+/// Real time taken by one video frame.
+const FRAME_TIME: Duration = Duration::from_nanos(FRAME_CYCLES * 1_000_000_000 / CLOCK_HZ);
 
 /// Struct representing a complete ZX Spectrum.
 pub struct RSSpectrum {
     /// Zilog Z80 CPU
     cpu: Z80,
     /// Complete 64KB memory
-    memory: [u8; MEM_SIZE],
+    memory: Ram,
+}
+
+impl Default for RSSpectrum {
+    /// A Spectrum that has just been switched on, with the CPU in its power-on state.
+    fn default() -> Self {
+        let mut cpu = Z80::default();
+        cpu.reset();
+        RSSpectrum {
+            cpu,
+            memory: Ram::new(),
+        }
+    }
 }
 
 impl RSSpectrum {
     /// Construct a new Spectrum.
     pub fn new() -> RSSpectrum {
-        RSSpectrum {
-            cpu: Default::default(),
-            memory: [0; MEM_SIZE],
-        }
+        Self::default()
     }
 
-    /// Launch the Spectrum.
-    pub fn run(&mut self) -> Option<()> {
-        self.cpu.run(&mut self.memory)
+    /// Press the reset button, restarting the CPU from address `0x0000`.
+    pub fn reset(&mut self) {
+        self.cpu.soft_reset();
+    }
+
+    /// Launch the Spectrum, returning only if the CPU meets an instruction it cannot decode.
+    ///
+    /// The CPU runs a frame's worth of T-states at a time, then sleeps until the frame
+    /// would have ended on real hardware.
+    pub fn run(&mut self) -> Result<(), DecodeError> {
+        let mut deadline = Instant::now();
+        loop {
+            let frame_end = self.cpu.cycles + FRAME_CYCLES;
+            while self.cpu.cycles < frame_end {
+                self.cpu.step(&mut self.memory)?;
+            }
+            deadline += FRAME_TIME;
+            if let Some(wait) = deadline.checked_duration_since(Instant::now()) {
+                thread::sleep(wait);
+            }
+        }
     }
 }
@@ -0,0 +1,29 @@
+#!/bin/sh
+# Compare the table-driven decoder with the options! decoder it replaced.
+#
+# Runs the decode benchmark on the last commit before the tables (5ba3bb6) and
+# saves it as the criterion baseline `options`, then runs the same benchmark on
+# the working tree against that baseline. Run from anywhere inside the repository.
+set -eu
+
+OLD=5ba3bb6
+root=$(git rev-parse --show-toplevel)
+tree=$(mktemp -d)
+trap 'git -C "$root" worktree remove --force "$tree"' EXIT
+
+export CARGO_TARGET_DIR="$root/target"
+
+git -C "$root" worktree add --detach "$tree" "$OLD"
+mkdir -p "$tree/rz80/benches"
+cp "$root/rz80/benches/decode.rs" "$tree/rz80/benches/"
+cp "$root/Cargo.lock" "$tree/"
+cat >>"$tree/rz80/Cargo.toml" <<'EOF'
+criterion = "0.8"
+
+[[bench]]
+name = "decode"
+harness = false
+EOF
+
+(cd "$tree" && cargo bench -p rz80 --bench decode -- --save-baseline options)
+(cd "$root" && cargo bench -p rz80 --bench decode -- --baseline options)
@@ -0,0 +1,51 @@
+//! Benchmarks for decoding Z80 instructions.
+//!
+//! `benches/compare-options.sh` runs this benchmark on the `options!` decoder that
+//! the opcode tables replaced, then on the working tree against it.
+use criterion::{criterion_group, criterion_main, Criterion};
+use rz80::Z80;
+use std::hint::black_box;
+
+/// A mix of common instructions from every opcode page.
+const PROGRAM: &[u8] = &[
+    0x00, // NOP
+    0x3e, 0x12, // LD A,0x12
+    0x78, // LD A,B
+    0x21, 0x34, 0x12, // LD HL,0x1234
+    0x7e, // LD A,(HL)
+    0x80, // ADD A,B
+    0xfe, 0x10, // CP 0x10
+    0x20, 0xfe, // JR NZ,-2
+    0xc3, 0x00, 0x80, // JP 0x8000
+    0xcd, 0x00, 0x90, // CALL 0x9000
+    0xc9, // RET
+    0xcb, 0x47, // BIT 0,A
+    0xcb, 0x27, // SLA A
+    0xed, 0xb0, // LDIR
+    0xed, 0x4b, 0x00, 0x40, // LD BC,(0x4000)
+    0xdd, 0x7e, 0x05, // LD A,(IX+5)
+    0xfd, 0x36, 0xfe, 0x99, // LD (IY-2),0x99
+    0xdd, 0xcb, 0x03, 0xc6, // SET 0,(IX+3)
+    0xfd, 0xcb, 0x01, 0x16, // RL (IY+1)
+    0xdd, 0x24, // INC IXH
+    0x10, 0xfe, // DJNZ -2
+];
+
+fn decode_program(c: &mut Criterion) {
+    let cpu: Z80 = Default::default();
+    let mut memory = PROGRAM.to_vec();
+    memory.extend_from_slice(&[0; 4]);
+
+    c.bench_function("decode program", |b| {
+        b.iter(|| {
+            let mut pc = 0;
+            while pc < PROGRAM.len() {
+                let (_, width) = cpu.decode(black_box(&memory[pc..])).unwrap();
+                pc += width as usize;
+            }
+        })
+    });
+}
+
+criterion_group!(benches, decode_program);
+criterion_main!(benches);
@@ -0,0 +1,157 @@
+//! Defines a trait through which the CPU accesses memory and I/O ports.
+use crate::io::IoBus;
+use std::ops::{Deref, DerefMut};
+
+/// Size in bytes of the Z80 address space.
+const ADDR_SPACE: usize = 0x10000;
+
+/// Trait for hosts that connect memory and I/O ports to the CPU.
+///
+/// Every memory access goes through the bus, so a host can write-protect ROM, switch
+/// banks, map peripherals into the address space, or count contended accesses. The
+/// I/O methods are inherited from [`IoBus`].
+///
+/// The CPU borrows its bus for each call to [`Z80::step`](crate::Z80::step) and its
+/// relatives rather than owning it. This keeps [`Z80`](crate::Z80) a plain value that
+/// can be cloned, compared, and serialized on its own, and leaves the machine that
+/// owns both the CPU and its memory free to inspect or change either between steps.
+///
+/// # Example
+/// ```
+/// # use rz80::{bus::Bus, io::IoBus};
+/// /// 16K of ROM followed by 48K of RAM.
+/// struct Spectrum48 {
+///     memory: Vec<u8>,
+/// }
+///
+/// impl Bus for Spectrum48 {
+///     fn read(&mut self, addr: u16) -> u8 {
+///         self.memory[addr as usize]
+///     }
+///
+///     fn write(&mut self, addr: u16, val: u8) {
+///         if addr >= 0x4000 {
+///             self.memory[addr as usize] = val;
+///         }
+///     }
+/// }
+///
+/// impl IoBus for Spectrum48 {
+///     fn port_in(&mut self, _port: u16) -> u8 {
+///         0xff
+///     }
+///
+///     fn port_out(&mut self, _port: u16, _val: u8) {}
+/// }
+///
+/// let mut bus = Spectrum48 { memory: vec![0; 0x10000] };
+/// bus.write(0x0000, 0x12);
+/// bus.write(0x4000, 0x34);
+/// assert_eq!(0x00, bus.read(0x0000));
+/// assert_eq!(0x34, bus.read(0x4000));
+/// ```
+pub trait Bus: IoBus {
+    /// Read a byte from memory.
+    ///
+    /// # Arguments
+    /// - `addr`: address to read
+    fn read(&mut self, addr: u16) -> u8;
+
+    /// Write a byte to memory.
+    ///
+    /// # Arguments
+    /// - `addr`: address to write
+    /// - `val`: value to write
+    fn write(&mut self, addr: u16, val: u8);
+
+    /// Read an opcode byte from memory during an M1 cycle.
+    ///
+    /// This is called for the first byte of every instruction and for the byte
+    /// following a `CB`, `ED`, `DD` or `FD` prefix. By default it is an ordinary
+    /// [`read`](Bus::read).
+    ///
+    /// # Arguments
+    /// - `addr`: address to read
+    fn fetch_opcode(&mut self, addr: u16) -> u8 {
+        self.read(addr)
+    }
+}
+
+/// A flat 64K of RAM, with every address readable and writable.
+///
+/// I/O is delegated to `io`, which by default is the empty bus `()`. The memory can be
+/// accessed directly as a byte slice.
+///
+/// # Example
+/// ```
+/// # use rz80::bus::{Bus, Ram};
+/// let mut ram = Ram::new();
+/// ram[0x8000..0x8002].copy_from_slice(&[0x3e, 0x12]);
+/// assert_eq!(0x12, ram.read(0x8001));
+/// ```
+pub struct Ram<P = ()> {
+    /// The entire address space
+    memory: Box<[u8]>,
+    /// The I/O ports connected alongside the memory
+    pub io: P,
+}
+
+impl Ram {
+    /// Construct 64K of zeroed RAM with no I/O ports.
+    pub fn new() -> Ram {
+        Ram::with_io(())
+    }
+}
+
+impl Default for Ram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: IoBus> Ram<P> {
+    /// Construct 64K of zeroed RAM with the given I/O ports.
+    ///
+    /// # Arguments
+    /// - `io`: the I/O ports to connect
+    pub fn with_io(io: P) -> Ram<P> {
+        Ram {
+            memory: vec![0; ADDR_SPACE].into_boxed_slice(),
+            io,
+        }
+    }
+}
+
+impl<P> Deref for Ram<P> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.memory
+    }
+}
+
+impl<P> DerefMut for Ram<P> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.memory
+    }
+}
+
+impl<P: IoBus> Bus for Ram<P> {
+    fn read(&mut self, addr: u16) -> u8 {
+        self.memory[addr as usize]
+    }
+
+    fn write(&mut self, addr: u16, val: u8) {
+        self.memory[addr as usize] = val;
+    }
+}
+
+impl<P: IoBus> IoBus for Ram<P> {
+    fn port_in(&mut self, port: u16) -> u8 {
+        self.io.port_in(port)
+    }
+
+    fn port_out(&mut self, port: u16, val: u8) {
+        self.io.port_out(port, val);
+    }
+}
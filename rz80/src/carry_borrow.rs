@@ -6,57 +6,130 @@ pub trait AddCarry : Sized + Add<Self> {
     /// Add this value to another, and return the sum along with two flags
     /// indicating whether a carry occured from bit 3 or bit 7 (least significant
     /// bit is bit 0).
-    /// 
+    ///
+    /// For `u16` the flags instead indicate a carry from bit 11 or bit 15.
+    ///
     /// # Arguments
     /// - `rhs`: second addend
-    /// 
+    ///
     /// # Example
     /// ```
     /// # use rz80::carry_borrow::AddCarry;
-    /// let (sum, carry3, carry7) = 0x1f.add_carry(0x21);
+    /// let (sum, carry3, carry7) = 0x1f_u8.add_carry(0x21);
     /// assert_eq!(0x40, sum);
     /// assert!(carry3);
     /// assert!(!carry7);
     /// ```
-    fn add_carry(self, rhs: Self) -> (Self, bool, bool);
+    fn add_carry(self, rhs: Self) -> (Self, bool, bool) {
+        self.add_carry_in(rhs, false)
+    }
+
+    /// Add this value, another, and an incoming carry bit, and return the sum
+    /// along with the same two carry flags as [`AddCarry::add_carry`].
+    ///
+    /// # Arguments
+    /// - `rhs`: second addend
+    /// - `carry`: whether to add an additional 1
+    ///
+    /// # Example
+    /// ```
+    /// # use rz80::carry_borrow::AddCarry;
+    /// let (sum, carry11, carry15) = 0x0fff_u16.add_carry_in(0xf000, true);
+    /// assert_eq!(0x0000, sum);
+    /// assert!(carry11);
+    /// assert!(carry15);
+    /// ```
+    fn add_carry_in(self, rhs: Self, carry: bool) -> (Self, bool, bool);
 }
 
 impl AddCarry for u8 {
-    fn add_carry(self, rhs: Self) -> (Self, bool, bool) {
+    fn add_carry_in(self, rhs: Self, carry: bool) -> (Self, bool, bool) {
+        let c = carry as u16;
         // bit 3 carry
-        let lo_sum = (self & 0x0f) + (rhs & 0x0f);
+        let lo_sum = (self & 0x0f) as u16 + (rhs & 0x0f) as u16 + c;
         let carry3 = lo_sum > 0x0f;
         // bit 7 carry
-        let hi_sum = (self as u16) + (rhs as u16);
+        let hi_sum = (self as u16) + (rhs as u16) + c;
         let carry7 = hi_sum > 0xff;
 
         ((hi_sum & 0xff) as u8, carry3, carry7)
     }
 }
 
+impl AddCarry for u16 {
+    fn add_carry_in(self, rhs: Self, carry: bool) -> (Self, bool, bool) {
+        let c = carry as u32;
+        // bit 11 carry
+        let lo_sum = (self & 0x0fff) as u32 + (rhs & 0x0fff) as u32 + c;
+        let carry11 = lo_sum > 0x0fff;
+        // bit 15 carry
+        let hi_sum = (self as u32) + (rhs as u32) + c;
+        let carry15 = hi_sum > 0xffff;
+
+        ((hi_sum & 0xffff) as u16, carry11, carry15)
+    }
+}
+
 /// Trait for types that can track borrows when they are subtracted.
 pub trait SubBorrow : Sized + Sub<Self> {
     /// Subtract another value from this, and return the difference along with
     /// two flags indicating whether a borrow was required from bit 4 (least
     /// significant bit is bit 0).
     ///
+    /// For `u16` the flag instead indicates a borrow from bit 12.
+    ///
     /// # Arguments
     /// - `rhs`: the subtrahend
-    /// 
+    ///
     /// # Example
     /// ```
     /// # use rz80::carry_borrow::SubBorrow;
-    /// let (diff, borrow) = 0x17.sub_borrow(0x08);
+    /// let (diff, borrow) = 0x17_u8.sub_borrow(0x08);
     /// assert_eq!(0x0f, diff);
     /// assert!(borrow);
     /// ```
-    fn sub_borrow(self, rhs: Self) -> (Self, bool);
+    fn sub_borrow(self, rhs: Self) -> (Self, bool) {
+        let (diff, half, _) = self.sub_borrow_in(rhs, false);
+        (diff, half)
+    }
+
+    /// Subtract another value and an incoming borrow bit from this, and return the
+    /// difference along with two flags indicating whether a borrow was required from
+    /// bit 4 and from beyond the most significant bit (bit 12 and bit 16 for `u16`).
+    ///
+    /// # Arguments
+    /// - `rhs`: the subtrahend
+    /// - `borrow`: whether to subtract an additional 1
+    ///
+    /// # Example
+    /// ```
+    /// # use rz80::carry_borrow::SubBorrow;
+    /// let (diff, borrow12, borrow16) = 0x1000_u16.sub_borrow_in(0x1000, true);
+    /// assert_eq!(0xffff, diff);
+    /// assert!(borrow12);
+    /// assert!(borrow16);
+    /// ```
+    fn sub_borrow_in(self, rhs: Self, borrow: bool) -> (Self, bool, bool);
 }
 
 impl SubBorrow for u8 {
-    fn sub_borrow(self, rhs: Self) -> (Self, bool) {
+    fn sub_borrow_in(self, rhs: Self, borrow: bool) -> (Self, bool, bool) {
+        let b = borrow as u8;
         let lo1 = self & 0x0f;
         let lo2 = rhs & 0x0f;
-        (self - rhs, lo1 < lo2)
+        let half = (lo1 as u16) < (lo2 as u16) + (b as u16);
+        let full = (self as u16) < (rhs as u16) + (b as u16);
+        (self.wrapping_sub(rhs).wrapping_sub(b), half, full)
     }
-}
\ No newline at end of file
+}
+
+impl SubBorrow for u16 {
+    fn sub_borrow_in(self, rhs: Self, borrow: bool) -> (Self, bool, bool) {
+        let b = borrow as u16;
+        let lo1 = self & 0x0fff;
+        let lo2 = rhs & 0x0fff;
+        let half = (lo1 as u32) < (lo2 as u32) + (b as u32);
+        let full = (self as u32) < (rhs as u32) + (b as u32);
+        (self.wrapping_sub(rhs).wrapping_sub(b), half, full)
+    }
+}
@@ -1,119 +1,244 @@
-//! Methods, macros, and helper functions for decoding Z80 instructions.
-mod arith8;
-mod exchange;
-mod jump;
-mod load8;
-
-use super::{Instruction, Condition, Register, Z80};
-use arith8::arith8;
-use exchange::exchange;
-use jump::jump;
-use load8::load8;
-
-/// Returns a [`Register`] if the provided three-bit value maps to a register name.
+//! Methods and helper functions for decoding Z80 instructions.
+//!
+//! Decoding is driven by one 256-entry table per opcode page. Each opcode is split
+//! into the fields `x` (bits 7-6), `y` (bits 5-3), and `z` (bits 2-0), with `y`
+//! further split into `p` (bits 5-4) and `q` (bit 3); the tables are built once, at
+//! compile time, by matching on these fields. Each entry is the function that reads
+//! the operands of that opcode and builds the [`Instruction`].
+mod cb;
+mod ed;
+mod index;
+mod unprefixed;
+
+use super::{Condition, Instruction, Register, Z80};
+use byteorder::{ByteOrder, LE};
+use std::{error, fmt};
+use unprefixed::UNPREFIXED;
+
+/// Reasons that an instruction could not be decoded.
 ///
-/// # Arguments
-/// - `bits`: the bits to convert
-#[inline]
-fn bits_to_reg(bits: u8) -> Option<Register> {
-    match bits {
-        0b111 => Some(Register::A),
-        0b000 => Some(Register::B),
-        0b001 => Some(Register::C),
-        0b010 => Some(Register::D),
-        0b011 => Some(Register::E),
-        0b100 => Some(Register::H),
-        0b101 => Some(Register::L),
-        _ => None,
-    }
+/// Every sequence of bytes is a Z80 instruction: undefined `ED` opcodes decode as
+/// [`Instruction::ED_NOP`] and ignored `DD` or `FD` prefixes as
+/// [`Instruction::PREFIX_NOP`], just as the CPU runs them. Decoding can therefore
+/// only fail when the input ends part way through an instruction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input ended before the end of the instruction.
+    Truncated {
+        /// Address of the first byte of the instruction
+        address: u16,
+    },
 }
 
-/// Returns a [`Condition`] if the provided three-bit value maps to a condition.
-/// 
-/// # Arguments
-/// - `bits`: the bits to convert
-#[inline]
-fn bits_to_condition(bits: u8) -> Option<Condition> {
-    match bits {
-        0b000 => Some(Condition::NZ),
-        0b001 => Some(Condition::Z),
-        0b010 => Some(Condition::NC),
-        0b011 => Some(Condition::C),
-        0b100 => Some(Condition::PO),
-        0b101 => Some(Condition::PE),
-        0b110 => Some(Condition::P),
-        0b111 => Some(Condition::M),
-        _ => None,
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let DecodeError::Truncated { address } = self;
+        write!(f, "instruction at {:04X} is truncated", address)
     }
 }
 
-/// Try a sequence of provided [`Option`] expressions in order and return the first [`Some`]
-/// value it finds, or [`None`] if none of the expressions succeed.
+impl error::Error for DecodeError {}
+
+/// A table entry ran off the end of the slice, before the address is known.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Truncated;
+
+/// If decoding succeeds, returns both the instruction and the number of bytes read.
+type DecodeResult = Result<(Instruction, u8), Truncated>;
+
+/// A table entry, which decodes the instruction beginning at the start of the slice.
 ///
-/// # Examples
-/// ```
-/// # #[macro_use(options)] extern crate rz80;
-/// # fn main() {
-/// let x: Option<u8> = options!(Some(1), None);
-/// assert_eq!(Some(1), x);
-/// let y: Option<u8> = options!(None, None, Some(2), Some(3));
-/// assert_eq!(Some(2), y);
-/// # }
-/// ```
+/// The slice always begins with the first byte of the instruction, including any
+/// prefixes, so the entry must know where its own opcode and operands lie.
+type DecodeFn = fn(&[u8]) -> DecodeResult;
+
+/// An opcode page, indexed by the opcode byte.
+type Table = [DecodeFn; 256];
+
+/// Registers selected by `y` or `z`.
 ///
-/// The result can be unwrapped:
-/// ```
-/// # #[macro_use(options)] extern crate rz80;
-/// # fn main() {
-/// let x: u8 = options!(unwrap Some(1), None);
-/// assert_eq!(1, x);
-/// # }
-/// ```
-/// 
-/// Unwrapping will panic in the normal way:
-/// ```should_panic
-/// # #[macro_use(options)] extern crate rz80;
-/// # fn main() {
-/// let x: u8 = options!(unwrap None, None);
-/// # }
-/// ```
-#[macro_export]
-macro_rules! options {
-    ($opt:expr) => {
-        $opt
-    };
+/// The value `110` selects `(HL)` rather than a register, and every table gives it a
+/// separate entry, so the `HL` in that slot is only a placeholder.
+const R: [Register; 8] = [
+    Register::B,
+    Register::C,
+    Register::D,
+    Register::E,
+    Register::H,
+    Register::L,
+    Register::HL,
+    Register::A,
+];
 
-    ($opt:expr, $($opts:expr),+) => {
-        {
-            let x = $opt;
-            match x {
-                Some(_) => x,
-                None => options!($($opts),+)
-            }
-        }
-    };
+/// Register pairs selected by `p`, for instructions that may use `SP`.
+const RP: [Register; 4] = [Register::BC, Register::DE, Register::HL, Register::SP];
 
-    (unwrap $($opts:expr),+) => {
-        options!($($opts),+).unwrap()
-    }
+/// Register pairs selected by `p`, for `PUSH` and `POP`.
+const RP2: [Register; 4] = [Register::BC, Register::DE, Register::HL, Register::AF];
+
+/// Conditions selected by `y`.
+const CC: [Condition; 8] = [
+    Condition::NZ,
+    Condition::Z,
+    Condition::NC,
+    Condition::C,
+    Condition::PO,
+    Condition::PE,
+    Condition::P,
+    Condition::M,
+];
+
+/// Returns bits 7-6 of an opcode.
+#[inline]
+const fn x(op: u8) -> u8 {
+    op >> 6
 }
 
-/// If decoding succeeds, returns both the instruction and the number of bytes read.
-type DecodeResult = Option<(Instruction, u8)>;
+/// Returns bits 5-3 of an opcode.
+#[inline]
+const fn y(op: u8) -> u8 {
+    (op >> 3) & 0b111
+}
+
+/// Returns bits 2-0 of an opcode.
+#[inline]
+const fn z(op: u8) -> u8 {
+    op & 0b111
+}
 
-/// Bit mask `11000000`.
-const TOP_TWO: u8 = 0b11000000;
-/// Bit mask `00111000`.
-const MID_THREE: u8 = 0b00111000;
-/// Bit mask `00000111`.
-const LOW_THREE: u8 = 0b00000111;
+/// Returns bits 5-4 of an opcode.
+#[inline]
+const fn p(op: u8) -> u8 {
+    (op >> 4) & 0b11
+}
+
+/// Returns bit 3 of an opcode.
+#[inline]
+const fn q(op: u8) -> u8 {
+    (op >> 3) & 1
+}
+
+/// Returns the byte at `i`, or fails if the slice is too short.
+#[inline]
+fn byte(mem: &[u8], i: usize) -> Result<u8, Truncated> {
+    mem.get(i).copied().ok_or(Truncated)
+}
+
+/// Returns the signed displacement at `i`, or fails if the slice is too short.
+#[inline]
+fn disp(mem: &[u8], i: usize) -> Result<i8, Truncated> {
+    byte(mem, i).map(|d| d as i8)
+}
+
+/// Returns the little-endian word starting at `i`, or fails if the slice is too short.
+#[inline]
+fn word(mem: &[u8], i: usize) -> Result<u16, Truncated> {
+    mem.get(i..i + 2).map(LE::read_u16).ok_or(Truncated)
+}
+
+/// Defines table entries for instructions that take no operands.
+///
+/// Each entry is written `name => Variant, width;`.
+macro_rules! fixed {
+    ($($name:ident => $inst:ident, $width:expr;)+) => {
+        $(
+            fn $name(_: &[u8]) -> DecodeResult {
+                Ok((Instruction::$inst, $width))
+            }
+        )+
+    };
+}
+use fixed;
 
 impl Z80 {
     /// Attempts to decode an instruction that begins at the start of the provided slice.
     ///
+    /// If decoding succeeds, returns both the instruction and the number of bytes read.
+    /// Errors report the program counter as the address of the instruction, since the
+    /// slice is expected to come from [`Z80::fetch`].
+    ///
     /// # Arguments
     /// - `memory`: slice containing the instruction to decode
-    pub fn decode(&self, memory: &[u8]) -> DecodeResult {
-        options!(load8(memory), exchange(memory), jump(memory), arith8(memory))
+    ///
+    /// # Example
+    /// ```
+    /// # use rz80::{DecodeError, Instruction, Z80};
+    /// let cpu: Z80 = Default::default();
+    /// assert_eq!(Ok((Instruction::LD_A_nn(0x5000), 3)), cpu.decode(&[0x3a, 0x00, 0x50]));
+    /// assert_eq!(Err(DecodeError::Truncated { address: 0 }), cpu.decode(&[0x3a, 0x00]));
+    /// ```
+    pub fn decode(&self, memory: &[u8]) -> Result<(Instruction, u8), DecodeError> {
+        decode_at(memory, self.prog_counter)
+    }
+}
+
+/// Decodes the instruction at the start of `memory`, reporting errors at `address`.
+pub(crate) fn decode_at(memory: &[u8], address: u16) -> Result<(Instruction, u8), DecodeError> {
+    let result = match memory.first() {
+        Some(op) => UNPREFIXED[*op as usize](memory),
+        None => Err(Truncated),
+    };
+    result.map_err(|Truncated| DecodeError::Truncated { address })
+}
+
+#[cfg(test)]
+mod decode_tests {
+    use super::*;
+    use proptest::prelude::*;
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[rstest]
+    #[case::ld_77(0x77)]
+    #[case::low(0x00)]
+    #[case::high(0xff)]
+    fn test_undefined_ed_opcode(z80: Z80, #[case] op: u8) {
+        assert_eq!(
+            Ok((Instruction::ED_NOP(op), 2)),
+            z80.decode(&[0xed, op, 0x00, 0x00])
+        );
+    }
+
+    #[rstest]
+    fn test_truncated_display() {
+        let err = DecodeError::Truncated { address: 0x8000 };
+        assert_eq!("instruction at 8000 is truncated", err.to_string());
+    }
+
+    #[rstest]
+    #[case::empty(&[])]
+    #[case::operand(&[0x06])]
+    #[case::word(&[0xc3, 0x00])]
+    #[case::ed(&[0xed])]
+    #[case::ed_word(&[0xed, 0x4b, 0x00])]
+    #[case::index_d(&[0xdd, 0x7e])]
+    #[case::index_n(&[0xfd, 0x36, 0x01])]
+    #[case::index_cb(&[0xdd, 0xcb, 0x01])]
+    fn test_truncated(mut z80: Z80, #[case] bytes: &[u8]) {
+        z80.prog_counter = 0x1234;
+        assert_eq!(
+            Err(DecodeError::Truncated { address: 0x1234 }),
+            z80.decode(bytes)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_decode_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..6)) {
+            let z80 = z80();
+            if let Ok((_, width)) = z80.decode(&bytes) {
+                prop_assert!(width as usize <= bytes.len());
+            }
+        }
+
+        #[test]
+        fn test_decode_every_opcode(bytes in prop::array::uniform4(any::<u8>())) {
+            let z80 = z80();
+            prop_assert!(z80.decode(&bytes).is_ok());
+        }
     }
 }
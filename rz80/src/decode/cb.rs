@@ -0,0 +1,120 @@
+//! Table for decoding `CB`-prefixed opcodes: rotates, shifts, and bit operations.
+use super::{x, y, z, DecodeFn, DecodeResult, Instruction, Register, Table, R};
+
+/// Opcodes prefixed by `CB`, indexed by the second byte of the instruction.
+pub static CB: Table = build();
+
+const fn build() -> Table {
+    let mut table = [rot_r as DecodeFn; 256];
+    let mut i = 0;
+    while i < 256 {
+        let op = i as u8;
+        table[i] = match (x(op), z(op)) {
+            (0, 6) => rot_hl,
+            (0, _) => rot_r,
+            (1, 6) => bit_b_hl,
+            (1, _) => bit_b_r,
+            (2, 6) => res_b_hl,
+            (2, _) => res_b_r,
+            (_, 6) => set_b_hl,
+            _ => set_b_r,
+        };
+        i += 1;
+    }
+    table
+}
+
+/// Returns the rotate or shift selected by `y` that operates on register `r`.
+#[inline]
+fn rot_reg(y: u8, r: Register) -> Instruction {
+    match y {
+        0 => Instruction::RLC_r(r),
+        1 => Instruction::RRC_r(r),
+        2 => Instruction::RL_r(r),
+        3 => Instruction::RR_r(r),
+        4 => Instruction::SLA_r(r),
+        5 => Instruction::SRA_r(r),
+        6 => Instruction::SLL_r(r),
+        _ => Instruction::SRL_r(r),
+    }
+}
+
+fn rot_r(mem: &[u8]) -> DecodeResult {
+    let r = R[z(mem[1]) as usize];
+    Ok((rot_reg(y(mem[1]), r), 2))
+}
+
+fn rot_hl(mem: &[u8]) -> DecodeResult {
+    let inst = match y(mem[1]) {
+        0 => Instruction::RLC_HL,
+        1 => Instruction::RRC_HL,
+        2 => Instruction::RL_HL,
+        3 => Instruction::RR_HL,
+        4 => Instruction::SLA_HL,
+        5 => Instruction::SRA_HL,
+        6 => Instruction::SLL_HL,
+        _ => Instruction::SRL_HL,
+    };
+    Ok((inst, 2))
+}
+
+fn bit_b_r(mem: &[u8]) -> DecodeResult {
+    let r = R[z(mem[1]) as usize];
+    Ok((Instruction::BIT_b_r(y(mem[1]), r), 2))
+}
+
+fn bit_b_hl(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::BIT_b_HL(y(mem[1])), 2))
+}
+
+fn res_b_r(mem: &[u8]) -> DecodeResult {
+    let r = R[z(mem[1]) as usize];
+    Ok((Instruction::RES_b_r(y(mem[1]), r), 2))
+}
+
+fn res_b_hl(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::RES_b_HL(y(mem[1])), 2))
+}
+
+fn set_b_r(mem: &[u8]) -> DecodeResult {
+    let r = R[z(mem[1]) as usize];
+    Ok((Instruction::SET_b_r(y(mem[1]), r), 2))
+}
+
+fn set_b_hl(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::SET_b_HL(y(mem[1])), 2))
+}
+
+#[cfg(test)]
+mod cb_tests {
+    use super::*;
+    use crate::Z80;
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[rstest]
+    #[case::rlc_b(&[0xcb, 0x00], Instruction::RLC_r(Register::B), 2)]
+    #[case::rr_hl(&[0xcb, 0x1e], Instruction::RR_HL, 2)]
+    #[case::sll_a(&[0xcb, 0x37], Instruction::SLL_r(Register::A), 2)]
+    #[case::srl_l(&[0xcb, 0x3d], Instruction::SRL_r(Register::L), 2)]
+    #[case::bit_0_b(&[0xcb, 0x40], Instruction::BIT_b_r(0, Register::B), 2)]
+    #[case::bit_7_hl(&[0xcb, 0x7e], Instruction::BIT_b_HL(7), 2)]
+    #[case::res_3_a(&[0xcb, 0x9f], Instruction::RES_b_r(3, Register::A), 2)]
+    #[case::res_1_hl(&[0xcb, 0x8e], Instruction::RES_b_HL(1), 2)]
+    #[case::set_5_l(&[0xcb, 0xed], Instruction::SET_b_r(5, Register::L), 2)]
+    #[case::set_6_hl(&[0xcb, 0xf6], Instruction::SET_b_HL(6), 2)]
+    fn test_cb(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    fn test_every_opcode_is_two_bytes(z80: Z80) {
+        for op in 0..=0xff {
+            assert_eq!(2, z80.decode(&[0xcb, op]).unwrap().1);
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Table for decoding `ED`-prefixed opcodes.
+use super::{fixed, p, q, word, x, y, z, DecodeFn, DecodeResult, Instruction, Table, R, RP};
+
+/// Opcodes prefixed by `ED`, indexed by the second byte of the instruction.
+///
+/// Opcodes outside the documented groups decode as `ED_NOP`.
+pub static ED: Table = build();
+
+const fn build() -> Table {
+    let mut table = [invalid as DecodeFn; 256];
+    let mut i = 0;
+    while i < 256 {
+        let op = i as u8;
+        table[i] = match (x(op), z(op)) {
+            (1, 0) if y(op) == 6 => in_c,
+            (1, 0) => in_r_c,
+            (1, 1) if y(op) == 6 => out_c_0,
+            (1, 1) => out_c_r,
+            (1, 2) if q(op) == 0 => sbc_hl_ss,
+            (1, 2) => adc_hl_ss,
+            (1, 3) if q(op) == 0 => ld_nn_dd,
+            (1, 3) => ld_dd_inn,
+            // NEG is mirrored in every `ED 01xxx100` slot
+            (1, 4) => neg,
+            // RETN is mirrored across the ED page wherever RETI is not
+            (1, 5) if y(op) == 1 => reti,
+            (1, 5) => retn,
+            // IM is mirrored in every `ED 01xxx110` slot
+            (1, 6) => im,
+            (1, 7) => match y(op) {
+                0 => ld_i_a,
+                1 => ld_r_a,
+                2 => ld_a_i,
+                3 => ld_a_r,
+                4 => rrd,
+                5 => rld,
+                _ => invalid,
+            },
+            (2, _) if y(op) >= 4 && z(op) <= 3 => match (y(op), z(op)) {
+                (4, 0) => ldi,
+                (4, 1) => cpi,
+                (4, 2) => ini,
+                (4, 3) => outi,
+                (5, 0) => ldd,
+                (5, 1) => cpd,
+                (5, 2) => ind,
+                (5, 3) => outd,
+                (6, 0) => ldir,
+                (6, 1) => cpir,
+                (6, 2) => inir,
+                (6, 3) => otir,
+                (_, 0) => lddr,
+                (_, 1) => cpdr,
+                (_, 2) => indr,
+                _ => otdr,
+            },
+            _ => invalid,
+        };
+        i += 1;
+    }
+    table
+}
+
+fixed! {
+    in_c => IN_C, 2;
+    out_c_0 => OUT_C_0, 2;
+    neg => NEG, 2;
+    reti => RETI, 2;
+    retn => RETN, 2;
+    ld_i_a => LD_I_A, 2;
+    ld_r_a => LD_R_A, 2;
+    ld_a_i => LD_A_I, 2;
+    ld_a_r => LD_A_R, 2;
+    rrd => RRD, 2;
+    rld => RLD, 2;
+    ldi => LDI, 2;
+    cpi => CPI, 2;
+    ini => INI, 2;
+    outi => OUTI, 2;
+    ldd => LDD, 2;
+    cpd => CPD, 2;
+    ind => IND, 2;
+    outd => OUTD, 2;
+    ldir => LDIR, 2;
+    cpir => CPIR, 2;
+    inir => INIR, 2;
+    otir => OTIR, 2;
+    lddr => LDDR, 2;
+    cpdr => CPDR, 2;
+    indr => INDR, 2;
+    otdr => OTDR, 2;
+}
+
+fn invalid(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::ED_NOP(mem[1]), 2))
+}
+
+fn in_r_c(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::IN_r_C(R[y(mem[1]) as usize]), 2))
+}
+
+fn out_c_r(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::OUT_C_r(R[y(mem[1]) as usize]), 2))
+}
+
+fn sbc_hl_ss(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::SBC_HL_ss(RP[p(mem[1]) as usize]), 2))
+}
+
+fn adc_hl_ss(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::ADC_HL_ss(RP[p(mem[1]) as usize]), 2))
+}
+
+fn ld_nn_dd(mem: &[u8]) -> DecodeResult {
+    let dd = RP[p(mem[1]) as usize];
+    Ok((Instruction::LD_nn_dd(word(mem, 2)?, dd), 4))
+}
+
+fn ld_dd_inn(mem: &[u8]) -> DecodeResult {
+    let dd = RP[p(mem[1]) as usize];
+    Ok((Instruction::LD_dd_inn(dd, word(mem, 2)?), 4))
+}
+
+/// The undefined mode in `y` selects IM 0.
+fn im(mem: &[u8]) -> DecodeResult {
+    let inst = match y(mem[1]) & 0b11 {
+        2 => Instruction::IM_1,
+        3 => Instruction::IM_2,
+        _ => Instruction::IM_0,
+    };
+    Ok((inst, 2))
+}
+
+#[cfg(test)]
+mod ed_tests {
+    use super::*;
+    use crate::{Register, Z80};
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[rstest]
+    #[case::ld_a_i(&[0xed, 0x57], Instruction::LD_A_I, 2)]
+    #[case::ld_a_r(&[0xed, 0x5f], Instruction::LD_A_R, 2)]
+    #[case::ld_i_a(&[0xed, 0x47], Instruction::LD_I_A, 2)]
+    #[case::ld_r_a(&[0xed, 0x4f], Instruction::LD_R_A, 2)]
+    #[case::de_inn(&[0xed, 0x5b, 0x00, 0x50], Instruction::LD_dd_inn(Register::DE, 0x5000), 4)]
+    #[case::nn_sp(&[0xed, 0x73, 0x00, 0x50], Instruction::LD_nn_dd(0x5000, Register::SP), 4)]
+    #[case::nn_hl(&[0xed, 0x63, 0x00, 0x50], Instruction::LD_nn_dd(0x5000, Register::HL), 4)]
+    fn test_load(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    #[case::ldi(&[0xed, 0xa0], Instruction::LDI, 2)]
+    #[case::ldir(&[0xed, 0xb0], Instruction::LDIR, 2)]
+    #[case::ldd(&[0xed, 0xa8], Instruction::LDD, 2)]
+    #[case::lddr(&[0xed, 0xb8], Instruction::LDDR, 2)]
+    #[case::cpi(&[0xed, 0xa1], Instruction::CPI, 2)]
+    #[case::cpir(&[0xed, 0xb1], Instruction::CPIR, 2)]
+    #[case::cpd(&[0xed, 0xa9], Instruction::CPD, 2)]
+    #[case::cpdr(&[0xed, 0xb9], Instruction::CPDR, 2)]
+    #[case::ini(&[0xed, 0xa2], Instruction::INI, 2)]
+    #[case::indr(&[0xed, 0xba], Instruction::INDR, 2)]
+    #[case::outi(&[0xed, 0xa3], Instruction::OUTI, 2)]
+    #[case::otdr(&[0xed, 0xbb], Instruction::OTDR, 2)]
+    fn test_block(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    #[case::adc_hl_sp(&[0xed, 0x7a], Instruction::ADC_HL_ss(Register::SP), 2)]
+    #[case::sbc_hl_bc(&[0xed, 0x42], Instruction::SBC_HL_ss(Register::BC), 2)]
+    #[case::rld(&[0xed, 0x6f], Instruction::RLD, 2)]
+    #[case::rrd(&[0xed, 0x67], Instruction::RRD, 2)]
+    #[case::neg(&[0xed, 0x44], Instruction::NEG, 2)]
+    #[case::neg_mirror(&[0xed, 0x7c], Instruction::NEG, 2)]
+    #[case::im_0(&[0xed, 0x46], Instruction::IM_0, 2)]
+    #[case::im_0_undefined(&[0xed, 0x4e], Instruction::IM_0, 2)]
+    #[case::im_1(&[0xed, 0x56], Instruction::IM_1, 2)]
+    #[case::im_2(&[0xed, 0x5e], Instruction::IM_2, 2)]
+    #[case::im_2_mirror(&[0xed, 0x7e], Instruction::IM_2, 2)]
+    #[case::reti(&[0xed, 0x4d], Instruction::RETI, 2)]
+    #[case::retn(&[0xed, 0x45], Instruction::RETN, 2)]
+    #[case::retn_mirror(&[0xed, 0x7d], Instruction::RETN, 2)]
+    fn test_misc(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    #[case::in_b_c(&[0xed, 0x40], Instruction::IN_r_C(Register::B), 2)]
+    #[case::in_a_c(&[0xed, 0x78], Instruction::IN_r_C(Register::A), 2)]
+    #[case::in_c(&[0xed, 0x70], Instruction::IN_C, 2)]
+    #[case::out_c_e(&[0xed, 0x59], Instruction::OUT_C_r(Register::E), 2)]
+    #[case::out_c_0(&[0xed, 0x71], Instruction::OUT_C_0, 2)]
+    fn test_io(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    #[case::low(&[0xed, 0x00])]
+    #[case::ld_77(&[0xed, 0x77])]
+    #[case::block_gap(&[0xed, 0xa4])]
+    #[case::high(&[0xed, 0xff])]
+    fn test_invalid(z80: Z80, #[case] bytes: &[u8]) {
+        assert_eq!(Ok((Instruction::ED_NOP(bytes[1]), 2)), z80.decode(bytes));
+    }
+}
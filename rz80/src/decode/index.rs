@@ -0,0 +1,449 @@
+//! Tables for decoding `DD`- and `FD`-prefixed opcodes, including `DD CB` and `FD CB`.
+//!
+//! The two prefixes share one implementation, where `IY` selects whether the prefix
+//! is `FD` rather than `DD`.
+use super::{
+    byte, disp, p, unprefixed::alu, unprefixed::alu_reg, word, x, y, z, DecodeFn, DecodeResult,
+    Instruction, Register, Table, R, RP,
+};
+
+/// Opcodes prefixed by `DD`, indexed by the second byte of the instruction.
+pub static INDEX_X: Table = build::<false>();
+/// Opcodes prefixed by `FD`, indexed by the second byte of the instruction.
+pub static INDEX_Y: Table = build::<true>();
+/// Opcodes prefixed by `DD CB`, indexed by the fourth byte of the instruction.
+static INDEX_X_CB: Table = build_cb::<false>();
+/// Opcodes prefixed by `FD CB`, indexed by the fourth byte of the instruction.
+static INDEX_Y_CB: Table = build_cb::<true>();
+
+/// Returns whether `r` is one of the registers replaced by a half of the index register.
+const fn is_hl(r: u8) -> bool {
+    r == 4 || r == 5
+}
+
+/// Build the table for a `DD` or `FD` page.
+///
+/// Where the unprefixed opcode operates on `H` or `L` (but not `(HL)`), the prefix
+/// selects the corresponding half of `IX` or `IY` instead. Any other opcode without a
+/// documented indexed form ignores the prefix, which then behaves as a `NOP` before
+/// the opcode is decoded normally. This also covers chains of prefixes, where only
+/// the last one takes effect.
+const fn build<const IY: bool>() -> Table {
+    let mut table = [prefix_nop as DecodeFn; 256];
+    let mut i = 0;
+    while i < 256 {
+        let op = i as u8;
+        table[i] = match op {
+            0x21 => ld_index_nn::<IY>,
+            0x22 => ld_nn_index::<IY>,
+            0x2a => ld_index_inn::<IY>,
+            0x23 => inc_index::<IY>,
+            0x2b => dec_index::<IY>,
+            0x09 | 0x19 | 0x29 | 0x39 => add_index_pp::<IY>,
+            0x34 => inc_id::<IY>,
+            0x35 => dec_id::<IY>,
+            0x36 => ld_id_n::<IY>,
+            0x76 => prefix_nop,
+            0xcb => index_cb::<IY>,
+            0xe1 => pop_index::<IY>,
+            0xe3 => ex_sp_index::<IY>,
+            0xe5 => push_index::<IY>,
+            0xe9 => jp_index::<IY>,
+            0xf9 => ld_sp_index::<IY>,
+            _ => match (x(op), y(op), z(op)) {
+                (0, r, 4) if is_hl(r) => inc_half::<IY>,
+                (0, r, 5) if is_hl(r) => dec_half::<IY>,
+                (0, r, 6) if is_hl(r) => ld_half_n::<IY>,
+                (1, _, 6) => ld_r_id::<IY>,
+                (1, 6, _) => ld_id_r::<IY>,
+                (1, r, r1) if is_hl(r) || is_hl(r1) => ld_half_half::<IY>,
+                (2, _, 6) => alu_id::<IY>,
+                (2, _, r) if is_hl(r) => alu_half::<IY>,
+                _ => prefix_nop,
+            },
+        };
+        i += 1;
+    }
+    table
+}
+
+/// Build the table for a `DD CB` or `FD CB` page.
+///
+/// When the low three bits of the opcode name a register rather than `(HL)`, rotates,
+/// shifts, `SET`, and `RES` also copy the result into that register; `BIT` ignores them.
+const fn build_cb<const IY: bool>() -> Table {
+    let mut table = [rot_id::<IY> as DecodeFn; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = match x(i as u8) {
+            0 => rot_id::<IY>,
+            1 => bit_b_id::<IY>,
+            2 => res_b_id::<IY>,
+            _ => set_b_id::<IY>,
+        };
+        i += 1;
+    }
+    table
+}
+
+/// Returns the index register selected by the prefix.
+#[inline]
+fn index<const IY: bool>() -> Register {
+    if IY {
+        Register::IY
+    } else {
+        Register::IX
+    }
+}
+
+/// Returns `r`, with `H` and `L` replaced by the halves of the index register.
+#[inline]
+fn half<const IY: bool>(r: Register) -> Register {
+    match (r, IY) {
+        (Register::H, false) => Register::IXH,
+        (Register::L, false) => Register::IXL,
+        (Register::H, true) => Register::IYH,
+        (Register::L, true) => Register::IYL,
+        _ => r,
+    }
+}
+
+/// Returns whichever instruction matches the prefix.
+#[inline]
+fn pick<const IY: bool>(ix: Instruction, iy: Instruction) -> Instruction {
+    if IY {
+        iy
+    } else {
+        ix
+    }
+}
+
+fn prefix_nop(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::PREFIX_NOP(mem[0]), 1))
+}
+
+fn ld_index_nn<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let nn = word(mem, 2)?;
+    Ok((
+        pick::<IY>(Instruction::LD_IX_nn(nn), Instruction::LD_IY_nn(nn)),
+        4,
+    ))
+}
+
+fn ld_nn_index<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let nn = word(mem, 2)?;
+    Ok((
+        pick::<IY>(Instruction::LD_nn_IX(nn), Instruction::LD_nn_IY(nn)),
+        4,
+    ))
+}
+
+fn ld_index_inn<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let nn = word(mem, 2)?;
+    Ok((
+        pick::<IY>(Instruction::LD_IX_inn(nn), Instruction::LD_IY_inn(nn)),
+        4,
+    ))
+}
+
+fn inc_index<const IY: bool>(_: &[u8]) -> DecodeResult {
+    Ok((Instruction::INC_ss(index::<IY>()), 2))
+}
+
+fn dec_index<const IY: bool>(_: &[u8]) -> DecodeResult {
+    Ok((Instruction::DEC_ss(index::<IY>()), 2))
+}
+
+/// The register pair uses the index register in place of `HL`.
+fn add_index_pp<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let pp = match RP[p(mem[1]) as usize] {
+        Register::HL => index::<IY>(),
+        r => r,
+    };
+    Ok((
+        pick::<IY>(Instruction::ADD_IX_pp(pp), Instruction::ADD_IY_rr(pp)),
+        2,
+    ))
+}
+
+fn inc_id<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let d = disp(mem, 2)?;
+    Ok((
+        pick::<IY>(Instruction::INC_IX(d), Instruction::INC_IY(d)),
+        3,
+    ))
+}
+
+fn dec_id<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let d = disp(mem, 2)?;
+    Ok((
+        pick::<IY>(Instruction::DEC_IX(d), Instruction::DEC_IY(d)),
+        3,
+    ))
+}
+
+fn ld_id_n<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let d = disp(mem, 2)?;
+    let n = byte(mem, 3)?;
+    Ok((
+        pick::<IY>(Instruction::LD_IX_n(d, n), Instruction::LD_IY_n(d, n)),
+        4,
+    ))
+}
+
+fn pop_index<const IY: bool>(_: &[u8]) -> DecodeResult {
+    Ok((pick::<IY>(Instruction::POP_IX, Instruction::POP_IY), 2))
+}
+
+fn ex_sp_index<const IY: bool>(_: &[u8]) -> DecodeResult {
+    Ok((pick::<IY>(Instruction::EX_SP_IX, Instruction::EX_SP_IY), 2))
+}
+
+fn push_index<const IY: bool>(_: &[u8]) -> DecodeResult {
+    Ok((pick::<IY>(Instruction::PUSH_IX, Instruction::PUSH_IY), 2))
+}
+
+fn jp_index<const IY: bool>(_: &[u8]) -> DecodeResult {
+    Ok((pick::<IY>(Instruction::JP_IX, Instruction::JP_IY), 2))
+}
+
+fn ld_sp_index<const IY: bool>(_: &[u8]) -> DecodeResult {
+    Ok((pick::<IY>(Instruction::LD_SP_IX, Instruction::LD_SP_IY), 2))
+}
+
+/// `H` and `L` name the real registers here, since `(HL)` is already replaced.
+fn ld_r_id<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let r = R[y(mem[1]) as usize];
+    let d = disp(mem, 2)?;
+    Ok((
+        pick::<IY>(Instruction::LD_r_IX(r, d), Instruction::LD_r_IY(r, d)),
+        3,
+    ))
+}
+
+/// `H` and `L` name the real registers here, since `(HL)` is already replaced.
+fn ld_id_r<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let r = R[z(mem[1]) as usize];
+    let d = disp(mem, 2)?;
+    Ok((
+        pick::<IY>(Instruction::LD_IX_r(d, r), Instruction::LD_IY_r(d, r)),
+        3,
+    ))
+}
+
+fn alu_id<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let d = disp(mem, 2)?;
+    let insts = if IY {
+        [
+            Instruction::ADD_A_IY(d),
+            Instruction::ADC_A_IY(d),
+            Instruction::SUB_A_IY(d),
+            Instruction::SBC_A_IY(d),
+            Instruction::AND_A_IY(d),
+            Instruction::XOR_A_IY(d),
+            Instruction::OR_A_IY(d),
+            Instruction::CP_IY(d),
+        ]
+    } else {
+        [
+            Instruction::ADD_A_IX(d),
+            Instruction::ADC_A_IX(d),
+            Instruction::SUB_A_IX(d),
+            Instruction::SBC_A_IX(d),
+            Instruction::AND_A_IX(d),
+            Instruction::XOR_A_IX(d),
+            Instruction::OR_A_IX(d),
+            Instruction::CP_IX(d),
+        ]
+    };
+    Ok((alu(y(mem[1]), insts), 3))
+}
+
+fn inc_half<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let r = half::<IY>(R[y(mem[1]) as usize]);
+    Ok((Instruction::INC_r(r), 2))
+}
+
+fn dec_half<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let r = half::<IY>(R[y(mem[1]) as usize]);
+    Ok((Instruction::DEC_r(r), 2))
+}
+
+fn ld_half_n<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let r = half::<IY>(R[y(mem[1]) as usize]);
+    Ok((Instruction::LD_r_n(r, byte(mem, 2)?), 3))
+}
+
+fn ld_half_half<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let r = half::<IY>(R[y(mem[1]) as usize]);
+    let r1 = half::<IY>(R[z(mem[1]) as usize]);
+    Ok((Instruction::LD_r_r(r, r1), 2))
+}
+
+fn alu_half<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let r = half::<IY>(R[z(mem[1]) as usize]);
+    Ok((alu_reg(y(mem[1]), r), 2))
+}
+
+/// Indexed bit instructions are encoded `DD CB d op`, with the displacement before
+/// the final opcode byte.
+fn index_cb<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let op = byte(mem, 3)?;
+    if IY {
+        INDEX_Y_CB[op as usize](mem)
+    } else {
+        INDEX_X_CB[op as usize](mem)
+    }
+}
+
+fn rot_id<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let d = mem[2] as i8;
+    let r = writeback(mem[3]);
+    let inst = match (y(mem[3]), IY) {
+        (0, false) => Instruction::RLC_IX(d, r),
+        (1, false) => Instruction::RRC_IX(d, r),
+        (2, false) => Instruction::RL_IX(d, r),
+        (3, false) => Instruction::RR_IX(d, r),
+        (4, false) => Instruction::SLA_IX(d, r),
+        (5, false) => Instruction::SRA_IX(d, r),
+        (6, false) => Instruction::SLL_IX(d, r),
+        (_, false) => Instruction::SRL_IX(d, r),
+        (0, true) => Instruction::RLC_IY(d, r),
+        (1, true) => Instruction::RRC_IY(d, r),
+        (2, true) => Instruction::RL_IY(d, r),
+        (3, true) => Instruction::RR_IY(d, r),
+        (4, true) => Instruction::SLA_IY(d, r),
+        (5, true) => Instruction::SRA_IY(d, r),
+        (6, true) => Instruction::SLL_IY(d, r),
+        (_, true) => Instruction::SRL_IY(d, r),
+    };
+    Ok((inst, 4))
+}
+
+/// Returns the register that also receives the result, if any.
+#[inline]
+fn writeback(op: u8) -> Option<Register> {
+    (z(op) != 6).then_some(R[z(op) as usize])
+}
+
+fn bit_b_id<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let (b, d) = (y(mem[3]), mem[2] as i8);
+    Ok((
+        pick::<IY>(Instruction::BIT_b_IX(b, d), Instruction::BIT_b_IY(b, d)),
+        4,
+    ))
+}
+
+fn res_b_id<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let (b, d, r) = (y(mem[3]), mem[2] as i8, writeback(mem[3]));
+    Ok((
+        pick::<IY>(
+            Instruction::RES_b_IX(b, d, r),
+            Instruction::RES_b_IY(b, d, r),
+        ),
+        4,
+    ))
+}
+
+fn set_b_id<const IY: bool>(mem: &[u8]) -> DecodeResult {
+    let (b, d, r) = (y(mem[3]), mem[2] as i8, writeback(mem[3]));
+    Ok((
+        pick::<IY>(
+            Instruction::SET_b_IX(b, d, r),
+            Instruction::SET_b_IY(b, d, r),
+        ),
+        4,
+    ))
+}
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    use crate::Z80;
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[rstest]
+    #[case::ld_b_ix(&[0xdd, 0x46, 0x05], Instruction::LD_r_IX(Register::B, 5), 3)]
+    #[case::ld_h_ix(&[0xdd, 0x66, 0x05], Instruction::LD_r_IX(Register::H, 5), 3)]
+    #[case::ld_a_iy(&[0xfd, 0x7e, 0xff], Instruction::LD_r_IY(Register::A, -1), 3)]
+    #[case::ld_ix_c(&[0xdd, 0x71, 0x02], Instruction::LD_IX_r(2, Register::C), 3)]
+    #[case::ld_iy_l(&[0xfd, 0x75, 0xfe], Instruction::LD_IY_r(-2, Register::L), 3)]
+    #[case::ld_ix_n(&[0xdd, 0x36, 0x01, 0x99], Instruction::LD_IX_n(1, 0x99), 4)]
+    #[case::ld_iy_n(&[0xfd, 0x36, 0xfe, 0x99], Instruction::LD_IY_n(-2, 0x99), 4)]
+    #[case::ix(&[0xdd, 0x21, 0xcd, 0xab], Instruction::LD_IX_nn(0xabcd), 4)]
+    #[case::iy(&[0xfd, 0x21, 0xcd, 0xab], Instruction::LD_IY_nn(0xabcd), 4)]
+    #[case::ix_inn(&[0xdd, 0x2a, 0x00, 0x50], Instruction::LD_IX_inn(0x5000), 4)]
+    #[case::nn_iy(&[0xfd, 0x22, 0x00, 0x50], Instruction::LD_nn_IY(0x5000), 4)]
+    #[case::sp_ix(&[0xdd, 0xf9], Instruction::LD_SP_IX, 2)]
+    #[case::push_iy(&[0xfd, 0xe5], Instruction::PUSH_IY, 2)]
+    #[case::pop_ix(&[0xdd, 0xe1], Instruction::POP_IX, 2)]
+    #[case::ex_sp_ix(&[0xdd, 0xe3], Instruction::EX_SP_IX, 2)]
+    #[case::ex_sp_iy(&[0xfd, 0xe3], Instruction::EX_SP_IY, 2)]
+    #[case::jp_ix(&[0xdd, 0xe9], Instruction::JP_IX, 2)]
+    #[case::jp_iy(&[0xfd, 0xe9], Instruction::JP_IY, 2)]
+    fn test_load_jump(
+        z80: Z80,
+        #[case] bytes: &[u8],
+        #[case] inst: Instruction,
+        #[case] width: u8,
+    ) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    #[case::add_a_ix(&[0xdd, 0x86, 0x01], Instruction::ADD_A_IX(1), 3)]
+    #[case::sbc_a_iy(&[0xfd, 0x9e, 0xff], Instruction::SBC_A_IY(-1), 3)]
+    #[case::xor_a_ix(&[0xdd, 0xae, 0x00], Instruction::XOR_A_IX(0), 3)]
+    #[case::cp_iy(&[0xfd, 0xbe, 0x7f], Instruction::CP_IY(127), 3)]
+    #[case::inc_ix_d(&[0xdd, 0x34, 0x03], Instruction::INC_IX(3), 3)]
+    #[case::dec_iy_d(&[0xfd, 0x35, 0x80], Instruction::DEC_IY(-128), 3)]
+    #[case::add_ix_ix(&[0xdd, 0x29], Instruction::ADD_IX_pp(Register::IX), 2)]
+    #[case::add_ix_sp(&[0xdd, 0x39], Instruction::ADD_IX_pp(Register::SP), 2)]
+    #[case::add_iy_iy(&[0xfd, 0x29], Instruction::ADD_IY_rr(Register::IY), 2)]
+    #[case::add_iy_bc(&[0xfd, 0x09], Instruction::ADD_IY_rr(Register::BC), 2)]
+    #[case::inc_ix(&[0xdd, 0x23], Instruction::INC_ss(Register::IX), 2)]
+    #[case::dec_iy(&[0xfd, 0x2b], Instruction::DEC_ss(Register::IY), 2)]
+    fn test_arith(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    #[case::sla_ix(&[0xdd, 0xcb, 0xfe, 0x26], Instruction::SLA_IX(-2, None), 4)]
+    #[case::sra_ix_c(&[0xdd, 0xcb, 0x05, 0x29], Instruction::SRA_IX(5, Some(Register::C)), 4)]
+    #[case::rl_iy(&[0xfd, 0xcb, 0x10, 0x16], Instruction::RL_IY(16, None), 4)]
+    #[case::rrc_iy_e(&[0xfd, 0xcb, 0x10, 0x0b], Instruction::RRC_IY(16, Some(Register::E)), 4)]
+    #[case::srl_iy(&[0xfd, 0xcb, 0x00, 0x3e], Instruction::SRL_IY(0, None), 4)]
+    #[case::bit_2_ix(&[0xdd, 0xcb, 0x03, 0x56], Instruction::BIT_b_IX(2, 3), 4)]
+    #[case::bit_2_ix_alias(&[0xdd, 0xcb, 0x03, 0x50], Instruction::BIT_b_IX(2, 3), 4)]
+    #[case::res_0_ix(&[0xdd, 0xcb, 0xff, 0x86], Instruction::RES_b_IX(0, -1, None), 4)]
+    #[case::set_7_iy_d(&[0xfd, 0xcb, 0x08, 0xfa], Instruction::SET_b_IY(7, 8, Some(Register::D)), 4)]
+    fn test_index_cb(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    #[case::ld_ixh_n(&[0xdd, 0x26, 0x12], Instruction::LD_r_n(Register::IXH, 0x12), 3)]
+    #[case::ld_iyl_n(&[0xfd, 0x2e, 0x34], Instruction::LD_r_n(Register::IYL, 0x34), 3)]
+    #[case::ld_b_ixl(&[0xdd, 0x45], Instruction::LD_r_r(Register::B, Register::IXL), 2)]
+    #[case::ld_iyh_a(&[0xfd, 0x67], Instruction::LD_r_r(Register::IYH, Register::A), 2)]
+    #[case::ld_ixh_ixl(&[0xdd, 0x65], Instruction::LD_r_r(Register::IXH, Register::IXL), 2)]
+    #[case::add_a_iyl(&[0xfd, 0x85], Instruction::ADD_A_r(Register::IYL), 2)]
+    #[case::sbc_a_ixh(&[0xdd, 0x9c], Instruction::SBC_A_r(Register::IXH), 2)]
+    #[case::cp_iyh(&[0xfd, 0xbc], Instruction::CP_r(Register::IYH), 2)]
+    #[case::inc_ixh(&[0xdd, 0x24], Instruction::INC_r(Register::IXH), 2)]
+    #[case::dec_iyl(&[0xfd, 0x2d], Instruction::DEC_r(Register::IYL), 2)]
+    #[case::ignored_ld(&[0xdd, 0x41], Instruction::PREFIX_NOP(0xdd), 1)]
+    #[case::ignored_inc(&[0xfd, 0x04], Instruction::PREFIX_NOP(0xfd), 1)]
+    #[case::ignored_ex(&[0xdd, 0xeb], Instruction::PREFIX_NOP(0xdd), 1)]
+    #[case::ignored_ed(&[0xdd, 0xed, 0x44], Instruction::PREFIX_NOP(0xdd), 1)]
+    #[case::chained(&[0xdd, 0xfd, 0x21, 0x00, 0x00], Instruction::PREFIX_NOP(0xdd), 1)]
+    #[case::halt(&[0xdd, 0x76, 0x00], Instruction::PREFIX_NOP(0xdd), 1)]
+    fn test_halves(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+}
@@ -0,0 +1,464 @@
+//! Table for decoding unprefixed opcodes, which also dispatches to the prefixed pages.
+use super::{
+    byte, cb::CB, disp, ed::ED, fixed, index::INDEX_X, index::INDEX_Y, p, q, word, x, y, z,
+    DecodeFn, DecodeResult, Instruction, Register, Table, CC, R, RP, RP2,
+};
+
+/// Opcodes without a prefix, indexed by the first byte of the instruction.
+pub static UNPREFIXED: Table = build();
+
+const fn build() -> Table {
+    let mut table = [nop as DecodeFn; 256];
+    let mut i = 0;
+    while i < 256 {
+        let op = i as u8;
+        table[i] = match (x(op), z(op)) {
+            (0, 0) => match y(op) {
+                0 => nop,
+                1 => ex_af_af1,
+                2 => djnz_e,
+                3 => jr_e,
+                _ => jr_cc_e,
+            },
+            (0, 1) if q(op) == 0 => ld_dd_nn,
+            (0, 1) => add_hl_ss,
+            (0, 2) => match (q(op), p(op)) {
+                (0, 0) => ld_bc_a,
+                (0, 1) => ld_de_a,
+                (0, 2) => ld_nn_hl,
+                (0, _) => ld_nn_a,
+                (_, 0) => ld_a_bc,
+                (_, 1) => ld_a_de,
+                (_, 2) => ld_hl_inn,
+                _ => ld_a_nn,
+            },
+            (0, 3) if q(op) == 0 => inc_ss,
+            (0, 3) => dec_ss,
+            (0, 4) if y(op) == 6 => inc_hl,
+            (0, 4) => inc_r,
+            (0, 5) if y(op) == 6 => dec_hl,
+            (0, 5) => dec_r,
+            (0, 6) if y(op) == 6 => ld_hl_n,
+            (0, 6) => ld_r_n,
+            (0, _) => match y(op) {
+                0 => rlca,
+                1 => rrca,
+                2 => rla,
+                3 => rra,
+                4 => daa,
+                5 => cpl,
+                6 => scf,
+                _ => ccf,
+            },
+            (1, 6) if y(op) == 6 => halt,
+            (1, 6) => ld_r_hl,
+            (1, _) if y(op) == 6 => ld_hl_r,
+            (1, _) => ld_r_r,
+            (2, 6) => alu_hl,
+            (2, _) => alu_r,
+            (_, 0) => ret_cc,
+            (_, 1) => match (q(op), p(op)) {
+                (0, _) => pop_qq,
+                (_, 0) => ret,
+                (_, 1) => exx,
+                (_, 2) => jp_hl,
+                _ => ld_sp_hl,
+            },
+            (_, 2) => jp_cc_nn,
+            (_, 3) => match y(op) {
+                0 => jp_nn,
+                1 => cb,
+                2 => out_n_a,
+                3 => in_a_n,
+                4 => ex_sp_hl,
+                5 => ex_de_hl,
+                6 => di,
+                _ => ei,
+            },
+            (_, 4) => call_cc_nn,
+            (_, 5) => match (q(op), p(op)) {
+                (0, _) => push_qq,
+                (_, 0) => call_nn,
+                (_, 1) => dd,
+                (_, 2) => ed,
+                _ => fd,
+            },
+            (_, 6) => alu_n,
+            _ => rst_p,
+        };
+        i += 1;
+    }
+    table
+}
+
+fixed! {
+    nop => NOP, 1;
+    ex_af_af1 => EX_AF_AF1, 1;
+    ld_bc_a => LD_BC_A, 1;
+    ld_de_a => LD_DE_A, 1;
+    ld_a_bc => LD_A_BC, 1;
+    ld_a_de => LD_A_DE, 1;
+    inc_hl => INC_HL, 1;
+    dec_hl => DEC_HL, 1;
+    rlca => RLCA, 1;
+    rrca => RRCA, 1;
+    rla => RLA, 1;
+    rra => RRA, 1;
+    daa => DAA, 1;
+    cpl => CPL, 1;
+    scf => SCF, 1;
+    ccf => CCF, 1;
+    halt => HALT, 1;
+    ret => RET, 1;
+    exx => EXX, 1;
+    jp_hl => JP_HL, 1;
+    ld_sp_hl => LD_SP_HL, 1;
+    ex_sp_hl => EX_SP_HL, 1;
+    ex_de_hl => EX_DE_HL, 1;
+    di => DI, 1;
+    ei => EI, 1;
+}
+
+/// Returns the 8-bit arithmetic or logic instruction selected by `y`, given the
+/// instruction for each operation.
+///
+/// The order matches the `ALU` group: `ADD`, `ADC`, `SUB`, `SBC`, `AND`, `XOR`, `OR`,
+/// `CP`.
+#[inline]
+pub fn alu(y: u8, insts: [Instruction; 8]) -> Instruction {
+    insts[y as usize]
+}
+
+/// Returns the `ALU` instruction selected by `y` that operates on register `r`.
+#[inline]
+pub fn alu_reg(y: u8, r: Register) -> Instruction {
+    alu(
+        y,
+        [
+            Instruction::ADD_A_r(r),
+            Instruction::ADC_A_r(r),
+            Instruction::SUB_A_r(r),
+            Instruction::SBC_A_r(r),
+            Instruction::AND_A_r(r),
+            Instruction::XOR_A_r(r),
+            Instruction::OR_A_r(r),
+            Instruction::CP_r(r),
+        ],
+    )
+}
+
+fn djnz_e(mem: &[u8]) -> DecodeResult {
+    let e = disp(mem, 1)?;
+    Ok((Instruction::DJNZ_e(e), 2))
+}
+
+fn jr_e(mem: &[u8]) -> DecodeResult {
+    let e = disp(mem, 1)?;
+    Ok((Instruction::JR_e(e), 2))
+}
+
+fn jr_cc_e(mem: &[u8]) -> DecodeResult {
+    let e = disp(mem, 1)?;
+    let inst = match y(mem[0]) {
+        4 => Instruction::JR_NZ_e(e),
+        5 => Instruction::JR_Z_e(e),
+        6 => Instruction::JR_NC_e(e),
+        _ => Instruction::JR_C_e(e),
+    };
+    Ok((inst, 2))
+}
+
+fn ld_dd_nn(mem: &[u8]) -> DecodeResult {
+    let dd = RP[p(mem[0]) as usize];
+    Ok((Instruction::LD_dd_nn(dd, word(mem, 1)?), 3))
+}
+
+fn add_hl_ss(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::ADD_HL_ss(RP[p(mem[0]) as usize]), 1))
+}
+
+fn ld_nn_hl(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::LD_nn_HL(word(mem, 1)?), 3))
+}
+
+fn ld_nn_a(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::LD_nn_A(word(mem, 1)?), 3))
+}
+
+fn ld_hl_inn(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::LD_HL_inn(word(mem, 1)?), 3))
+}
+
+fn ld_a_nn(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::LD_A_nn(word(mem, 1)?), 3))
+}
+
+fn inc_ss(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::INC_ss(RP[p(mem[0]) as usize]), 1))
+}
+
+fn dec_ss(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::DEC_ss(RP[p(mem[0]) as usize]), 1))
+}
+
+fn inc_r(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::INC_r(R[y(mem[0]) as usize]), 1))
+}
+
+fn dec_r(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::DEC_r(R[y(mem[0]) as usize]), 1))
+}
+
+fn ld_hl_n(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::LD_HL_n(byte(mem, 1)?), 2))
+}
+
+fn ld_r_n(mem: &[u8]) -> DecodeResult {
+    let r = R[y(mem[0]) as usize];
+    Ok((Instruction::LD_r_n(r, byte(mem, 1)?), 2))
+}
+
+fn ld_r_hl(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::LD_r_HL(R[y(mem[0]) as usize]), 1))
+}
+
+fn ld_hl_r(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::LD_HL_r(R[z(mem[0]) as usize]), 1))
+}
+
+fn ld_r_r(mem: &[u8]) -> DecodeResult {
+    let r = R[y(mem[0]) as usize];
+    let r1 = R[z(mem[0]) as usize];
+    Ok((Instruction::LD_r_r(r, r1), 1))
+}
+
+fn alu_hl(mem: &[u8]) -> DecodeResult {
+    let inst = alu(
+        y(mem[0]),
+        [
+            Instruction::ADD_A_HL,
+            Instruction::ADC_A_HL,
+            Instruction::SUB_A_HL,
+            Instruction::SBC_A_HL,
+            Instruction::AND_A_HL,
+            Instruction::XOR_A_HL,
+            Instruction::OR_A_HL,
+            Instruction::CP_HL,
+        ],
+    );
+    Ok((inst, 1))
+}
+
+fn alu_r(mem: &[u8]) -> DecodeResult {
+    let r = R[z(mem[0]) as usize];
+    Ok((alu_reg(y(mem[0]), r), 1))
+}
+
+fn alu_n(mem: &[u8]) -> DecodeResult {
+    let n = byte(mem, 1)?;
+    let inst = alu(
+        y(mem[0]),
+        [
+            Instruction::ADD_A_n(n),
+            Instruction::ADC_A_n(n),
+            Instruction::SUB_A_n(n),
+            Instruction::SBC_A_n(n),
+            Instruction::AND_A_n(n),
+            Instruction::XOR_A_n(n),
+            Instruction::OR_A_n(n),
+            Instruction::CP_n(n),
+        ],
+    );
+    Ok((inst, 2))
+}
+
+fn ret_cc(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::RET_cc(CC[y(mem[0]) as usize]), 1))
+}
+
+fn pop_qq(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::POP_qq(RP2[p(mem[0]) as usize]), 1))
+}
+
+fn push_qq(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::PUSH_qq(RP2[p(mem[0]) as usize]), 1))
+}
+
+fn jp_cc_nn(mem: &[u8]) -> DecodeResult {
+    let cc = CC[y(mem[0]) as usize];
+    Ok((Instruction::JP_cc_nn(cc, word(mem, 1)?), 3))
+}
+
+fn jp_nn(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::JP_nn(word(mem, 1)?), 3))
+}
+
+fn out_n_a(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::OUT_n_A(byte(mem, 1)?), 2))
+}
+
+fn in_a_n(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::IN_A_n(byte(mem, 1)?), 2))
+}
+
+fn call_cc_nn(mem: &[u8]) -> DecodeResult {
+    let cc = CC[y(mem[0]) as usize];
+    Ok((Instruction::CALL_cc_nn(cc, word(mem, 1)?), 3))
+}
+
+fn call_nn(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::CALL_nn(word(mem, 1)?), 3))
+}
+
+fn rst_p(mem: &[u8]) -> DecodeResult {
+    Ok((Instruction::RST_p(y(mem[0]) << 3), 1))
+}
+
+fn cb(mem: &[u8]) -> DecodeResult {
+    CB[byte(mem, 1)? as usize](mem)
+}
+
+fn ed(mem: &[u8]) -> DecodeResult {
+    ED[byte(mem, 1)? as usize](mem)
+}
+
+fn dd(mem: &[u8]) -> DecodeResult {
+    INDEX_X[byte(mem, 1)? as usize](mem)
+}
+
+fn fd(mem: &[u8]) -> DecodeResult {
+    INDEX_Y[byte(mem, 1)? as usize](mem)
+}
+
+#[cfg(test)]
+mod unprefixed_tests {
+    use super::*;
+    use crate::{Condition, DecodeError, Z80};
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[rstest]
+    #[case::ld_a_b(&[0x78], Instruction::LD_r_r(Register::A, Register::B), 1)]
+    #[case::ld_h_l(&[0x65], Instruction::LD_r_r(Register::H, Register::L), 1)]
+    #[case::ld_d_n(&[0x16, 0x12], Instruction::LD_r_n(Register::D, 0x12), 2)]
+    #[case::ld_e_hl(&[0x5e], Instruction::LD_r_HL(Register::E), 1)]
+    #[case::ld_hl_c(&[0x71], Instruction::LD_HL_r(Register::C), 1)]
+    #[case::ld_hl_n(&[0x36, 0x99], Instruction::LD_HL_n(0x99), 2)]
+    #[case::ld_a_bc(&[0x0a], Instruction::LD_A_BC, 1)]
+    #[case::ld_a_de(&[0x1a], Instruction::LD_A_DE, 1)]
+    #[case::ld_a_nn(&[0x3a, 0x00, 0x50], Instruction::LD_A_nn(0x5000), 3)]
+    #[case::ld_bc_a(&[0x02], Instruction::LD_BC_A, 1)]
+    #[case::ld_de_a(&[0x12], Instruction::LD_DE_A, 1)]
+    #[case::ld_nn_a(&[0x32, 0x00, 0x50], Instruction::LD_nn_A(0x5000), 3)]
+    fn test_load8(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    #[case::bc(&[0x01, 0x34, 0x12], Instruction::LD_dd_nn(Register::BC, 0x1234), 3)]
+    #[case::sp(&[0x31, 0x00, 0xff], Instruction::LD_dd_nn(Register::SP, 0xff00), 3)]
+    #[case::hl_inn(&[0x2a, 0x00, 0x50], Instruction::LD_HL_inn(0x5000), 3)]
+    #[case::nn_hl(&[0x22, 0x00, 0x50], Instruction::LD_nn_HL(0x5000), 3)]
+    #[case::sp_hl(&[0xf9], Instruction::LD_SP_HL, 1)]
+    #[case::push_af(&[0xf5], Instruction::PUSH_qq(Register::AF), 1)]
+    #[case::pop_bc(&[0xc1], Instruction::POP_qq(Register::BC), 1)]
+    fn test_load16(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    #[case::ex_de_hl(&[0xeb], Instruction::EX_DE_HL, 1)]
+    #[case::ex_af_af1(&[0x08], Instruction::EX_AF_AF1, 1)]
+    #[case::exx(&[0xd9], Instruction::EXX, 1)]
+    #[case::ex_sp_hl(&[0xe3], Instruction::EX_SP_HL, 1)]
+    fn test_exchange(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    #[case::add_a_b(&[0x80], Instruction::ADD_A_r(Register::B), 1)]
+    #[case::adc_a_n(&[0xce, 0x05], Instruction::ADC_A_n(0x05), 2)]
+    #[case::sub_a_hl(&[0x96], Instruction::SUB_A_HL, 1)]
+    #[case::sbc_a_a(&[0x9f], Instruction::SBC_A_r(Register::A), 1)]
+    #[case::and_a_n(&[0xe6, 0x0f], Instruction::AND_A_n(0x0f), 2)]
+    #[case::xor_a_c(&[0xa9], Instruction::XOR_A_r(Register::C), 1)]
+    #[case::or_a_hl(&[0xb6], Instruction::OR_A_HL, 1)]
+    #[case::cp_n(&[0xfe, 0x10], Instruction::CP_n(0x10), 2)]
+    #[case::inc_d(&[0x14], Instruction::INC_r(Register::D), 1)]
+    #[case::dec_hl(&[0x35], Instruction::DEC_HL, 1)]
+    #[case::add_hl_de(&[0x19], Instruction::ADD_HL_ss(Register::DE), 1)]
+    #[case::inc_hl(&[0x23], Instruction::INC_ss(Register::HL), 1)]
+    #[case::dec_sp(&[0x3b], Instruction::DEC_ss(Register::SP), 1)]
+    fn test_arith(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    #[case::rlca(&[0x07], Instruction::RLCA, 1)]
+    #[case::rrca(&[0x0f], Instruction::RRCA, 1)]
+    #[case::rla(&[0x17], Instruction::RLA, 1)]
+    #[case::rra(&[0x1f], Instruction::RRA, 1)]
+    #[case::daa(&[0x27], Instruction::DAA, 1)]
+    #[case::cpl(&[0x2f], Instruction::CPL, 1)]
+    #[case::ccf(&[0x3f], Instruction::CCF, 1)]
+    #[case::scf(&[0x37], Instruction::SCF, 1)]
+    #[case::nop(&[0x00], Instruction::NOP, 1)]
+    #[case::halt(&[0x76], Instruction::HALT, 1)]
+    #[case::di(&[0xf3], Instruction::DI, 1)]
+    #[case::ei(&[0xfb], Instruction::EI, 1)]
+    fn test_control(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    #[case::jp(&[0xc3, 0x00, 0x80], Instruction::JP_nn(0x8000), 3)]
+    #[case::jp_pe(&[0xea, 0x00, 0x80], Instruction::JP_cc_nn(Condition::PE, 0x8000), 3)]
+    #[case::jp_hl(&[0xe9], Instruction::JP_HL, 1)]
+    #[case::jr(&[0x18, 0x03], Instruction::JR_e(3), 2)]
+    #[case::jr_nz(&[0x20, 0xfe], Instruction::JR_NZ_e(-2), 2)]
+    #[case::jr_z(&[0x28, 0x00], Instruction::JR_Z_e(0), 2)]
+    #[case::jr_nc(&[0x30, 0x10], Instruction::JR_NC_e(0x10), 2)]
+    #[case::jr_c(&[0x38, 0x80], Instruction::JR_C_e(-128), 2)]
+    #[case::djnz(&[0x10, 0xfe], Instruction::DJNZ_e(-2), 2)]
+    fn test_jump(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    #[case::call(&[0xcd, 0x34, 0x12], Instruction::CALL_nn(0x1234), 3)]
+    #[case::call_nz(&[0xc4, 0x34, 0x12], Instruction::CALL_cc_nn(Condition::NZ, 0x1234), 3)]
+    #[case::call_m(&[0xfc, 0x34, 0x12], Instruction::CALL_cc_nn(Condition::M, 0x1234), 3)]
+    #[case::ret(&[0xc9], Instruction::RET, 1)]
+    #[case::ret_c(&[0xd8], Instruction::RET_cc(Condition::C), 1)]
+    #[case::ret_po(&[0xe0], Instruction::RET_cc(Condition::PO), 1)]
+    #[case::rst_00(&[0xc7], Instruction::RST_p(0x00), 1)]
+    #[case::rst_28(&[0xef], Instruction::RST_p(0x28), 1)]
+    #[case::rst_38(&[0xff], Instruction::RST_p(0x38), 1)]
+    fn test_call(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    #[case::in_a_n(&[0xdb, 0xfe], Instruction::IN_A_n(0xfe), 2)]
+    #[case::out_n_a(&[0xd3, 0xfe], Instruction::OUT_n_A(0xfe), 2)]
+    fn test_io(z80: Z80, #[case] bytes: &[u8], #[case] inst: Instruction, #[case] width: u8) {
+        assert_eq!(Ok((inst, width)), z80.decode(bytes));
+    }
+
+    #[rstest]
+    #[case::ld_r_n(&[0x06])]
+    #[case::ld_a_nn(&[0x3a, 0x00])]
+    #[case::jr(&[0x18])]
+    #[case::call(&[0xcd, 0x00])]
+    #[case::empty(&[])]
+    fn test_short(z80: Z80, #[case] bytes: &[u8]) {
+        assert_eq!(
+            Err(DecodeError::Truncated { address: 0 }),
+            z80.decode(bytes)
+        );
+    }
+}
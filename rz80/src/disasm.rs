@@ -0,0 +1,680 @@
+//! Formats instructions in Zilog syntax and disassembles blocks of memory.
+use crate::{decode::decode_at, Condition, DecodeError, Instruction, Register};
+use std::fmt::{self, Write};
+
+/// How hexadecimal numbers are written in disassembled instructions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HexStyle {
+    /// Zilog style, with an `H` suffix and a leading zero if needed, as in `0FFH`
+    #[default]
+    Suffix,
+    /// A `$` prefix, as in `$FF`
+    Dollar,
+    /// A C-style `0x` prefix, as in `0xFF`
+    Prefix,
+}
+
+/// Options for writing instructions as text.
+///
+/// Immediate values and addresses are written in hexadecimal. Displacements, bit
+/// numbers and interrupt modes are written in decimal, and relative jumps are written
+/// as an offset from the start of the instruction, as in `JR $+5`.
+///
+/// # Example
+/// ```
+/// # use rz80::{disasm::{HexStyle, Syntax}, Instruction, Register};
+/// let inst = Instruction::LD_r_n(Register::A, 0xff);
+/// assert_eq!("LD A,0FFH", inst.to_string());
+/// let syntax = Syntax {
+///     hex: HexStyle::Prefix,
+///     lowercase: true,
+/// };
+/// assert_eq!("ld a,0xff", inst.display_with(syntax).to_string());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Syntax {
+    /// How immediate values and addresses are written
+    pub hex: HexStyle,
+    /// Whether to write mnemonics, registers and hex digits in lower case
+    pub lowercase: bool,
+}
+
+/// An instruction to be displayed in a given [`Syntax`], returned by
+/// [`Instruction::display_with`].
+#[derive(Clone, Copy, Debug)]
+pub struct Formatted {
+    instruction: Instruction,
+    syntax: Syntax,
+}
+
+impl fmt::Display for Formatted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut text = String::new();
+        write_instruction(&mut text, self.instruction, self.syntax.hex)?;
+        if self.syntax.lowercase {
+            text.make_ascii_lowercase();
+        }
+        f.pad(&text)
+    }
+}
+
+impl Instruction {
+    /// Returns a value that displays this instruction in the given syntax.
+    ///
+    /// # Arguments
+    /// - `syntax`: how to write the instruction
+    pub fn display_with(self, syntax: Syntax) -> Formatted {
+        Formatted {
+            instruction: self,
+            syntax,
+        }
+    }
+}
+
+/// Displays the instruction in Zilog syntax, with upper case and `H`-suffixed hex.
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display_with(Syntax::default()).fmt(f)
+    }
+}
+
+/// A decoded instruction within a block of memory, yielded by [`disassemble`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Line<'a> {
+    /// Address of the first byte of the instruction
+    pub address: u16,
+    /// Bytes of the instruction, including prefixes, so its length is `bytes.len()`
+    pub bytes: &'a [u8],
+    /// The decoded instruction
+    pub instruction: Instruction,
+}
+
+/// Iterator over the instructions in a block of memory, returned by [`disassemble`].
+#[derive(Clone, Debug)]
+pub struct Disassembly<'a> {
+    /// The block being disassembled
+    memory: &'a [u8],
+    /// Address of the first byte of the block
+    origin: u16,
+    /// Offset within the block of the next instruction
+    offset: usize,
+}
+
+impl<'a> Iterator for Disassembly<'a> {
+    type Item = Result<Line<'a>, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.memory[self.offset.min(self.memory.len())..];
+        if rest.is_empty() {
+            return None;
+        }
+        let address = self.origin.wrapping_add(self.offset as u16);
+        match decode_at(rest, address) {
+            Ok((instruction, width)) => {
+                let bytes = &rest[..width as usize];
+                self.offset += bytes.len();
+                Some(Ok(Line {
+                    address,
+                    bytes,
+                    instruction,
+                }))
+            }
+            Err(e) => {
+                self.offset = self.memory.len();
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Disassemble the instructions in a block of memory.
+///
+/// Undefined `ED` opcodes and ignored `DD` or `FD` prefixes are shown as `DB` data,
+/// since the CPU runs them as no-ops. An instruction cut off by the end of the block
+/// is reported as truncated and ends the disassembly.
+///
+/// Addresses wrap around from `0xFFFF` to `0x0000`, but the block itself does not:
+/// an instruction that straddles the top of memory is reported as truncated, since
+/// its last bytes lie at the start of memory rather than at the end of the block.
+/// Use [`Z80::fetch`](crate::Z80::fetch) to decode such an instruction from a
+/// [`Bus`](crate::bus::Bus).
+///
+/// # Arguments
+/// - `memory`: the block to disassemble
+/// - `origin`: address of the first byte of the block
+///
+/// # Example
+/// ```
+/// # use rz80::disasm::disassemble;
+/// let code = [0x3e, 0x12, 0xdd, 0x77, 0xfd, 0xc9];
+/// let lines: Vec<String> = disassemble(&code, 0x8000)
+///     .map(|line| {
+///         let line = line.unwrap();
+///         format!("{:04X} {}", line.address, line.instruction)
+///     })
+///     .collect();
+/// assert_eq!(vec!["8000 LD A,12H", "8002 LD (IX-3),A", "8005 RET"], lines);
+/// ```
+pub fn disassemble(memory: &[u8], origin: u16) -> Disassembly<'_> {
+    Disassembly {
+        memory,
+        origin,
+        offset: 0,
+    }
+}
+
+/// An operand of an instruction, as written in Zilog syntax.
+#[derive(Clone, Copy)]
+enum Operand {
+    /// A register or register pair
+    Reg(Register),
+    /// Fixed text, such as `(HL)`
+    Text(&'static str),
+    /// A condition code
+    Cond(Condition),
+    /// An 8-bit immediate value
+    Byte(u8),
+    /// A 16-bit immediate value or address
+    Word(u16),
+    /// A memory operand at an absolute address, as in `(nn)`
+    Addr(u16),
+    /// An I/O port given by an immediate byte, as in `(n)`
+    Port(u8),
+    /// An indexed memory operand, as in `(IX+d)`
+    Index(&'static str, i8),
+    /// The displacement of a relative jump
+    Rel(i8),
+    /// A bit number or interrupt mode
+    Num(u8),
+}
+
+use Operand::*;
+
+const A: Operand = Reg(Register::A);
+const HL: Operand = Reg(Register::HL);
+const IX: Operand = Reg(Register::IX);
+const IY: Operand = Reg(Register::IY);
+const SP: Operand = Reg(Register::SP);
+const MEM_HL: Operand = Text("(HL)");
+const PORT_C: Operand = Text("(C)");
+
+/// Returns the `(IX+d)` operand.
+fn ix(d: i8) -> Operand {
+    Index("IX", d)
+}
+
+/// Returns the `(IY+d)` operand.
+fn iy(d: i8) -> Operand {
+    Index("IY", d)
+}
+
+/// Returns the operands of an undocumented indexed instruction, which also copies its
+/// result to `r`.
+fn copy_to(mut ops: Vec<Operand>, r: Option<Register>) -> Vec<Operand> {
+    ops.extend(r.map(Reg));
+    ops
+}
+
+/// Returns the mnemonic and operands of an instruction.
+fn parts(inst: Instruction) -> (&'static str, Vec<Operand>) {
+    use Instruction as I;
+    match inst {
+        // 8-bit Load
+        I::LD_r_r(r, r1) => ("LD", vec![Reg(r), Reg(r1)]),
+        I::LD_r_n(r, n) => ("LD", vec![Reg(r), Byte(n)]),
+        I::LD_r_HL(r) => ("LD", vec![Reg(r), MEM_HL]),
+        I::LD_r_IX(r, d) => ("LD", vec![Reg(r), ix(d)]),
+        I::LD_r_IY(r, d) => ("LD", vec![Reg(r), iy(d)]),
+        I::LD_HL_r(r) => ("LD", vec![MEM_HL, Reg(r)]),
+        I::LD_IX_r(d, r) => ("LD", vec![ix(d), Reg(r)]),
+        I::LD_IY_r(d, r) => ("LD", vec![iy(d), Reg(r)]),
+        I::LD_HL_n(n) => ("LD", vec![MEM_HL, Byte(n)]),
+        I::LD_IX_n(d, n) => ("LD", vec![ix(d), Byte(n)]),
+        I::LD_IY_n(d, n) => ("LD", vec![iy(d), Byte(n)]),
+        I::LD_A_BC => ("LD", vec![A, Text("(BC)")]),
+        I::LD_A_DE => ("LD", vec![A, Text("(DE)")]),
+        I::LD_A_nn(nn) => ("LD", vec![A, Addr(nn)]),
+        I::LD_BC_A => ("LD", vec![Text("(BC)"), A]),
+        I::LD_DE_A => ("LD", vec![Text("(DE)"), A]),
+        I::LD_nn_A(nn) => ("LD", vec![Addr(nn), A]),
+        I::LD_A_I => ("LD", vec![A, Reg(Register::I)]),
+        I::LD_A_R => ("LD", vec![A, Reg(Register::R)]),
+        I::LD_I_A => ("LD", vec![Reg(Register::I), A]),
+        I::LD_R_A => ("LD", vec![Reg(Register::R), A]),
+        // 16-bit Load
+        I::LD_dd_nn(dd, nn) => ("LD", vec![Reg(dd), Word(nn)]),
+        I::LD_IX_nn(nn) => ("LD", vec![IX, Word(nn)]),
+        I::LD_IY_nn(nn) => ("LD", vec![IY, Word(nn)]),
+        I::LD_HL_inn(nn) => ("LD", vec![HL, Addr(nn)]),
+        I::LD_dd_inn(dd, nn) => ("LD", vec![Reg(dd), Addr(nn)]),
+        I::LD_IX_inn(nn) => ("LD", vec![IX, Addr(nn)]),
+        I::LD_IY_inn(nn) => ("LD", vec![IY, Addr(nn)]),
+        I::LD_nn_HL(nn) => ("LD", vec![Addr(nn), HL]),
+        I::LD_nn_dd(nn, dd) => ("LD", vec![Addr(nn), Reg(dd)]),
+        I::LD_nn_IX(nn) => ("LD", vec![Addr(nn), IX]),
+        I::LD_nn_IY(nn) => ("LD", vec![Addr(nn), IY]),
+        I::LD_SP_HL => ("LD", vec![SP, HL]),
+        I::LD_SP_IX => ("LD", vec![SP, IX]),
+        I::LD_SP_IY => ("LD", vec![SP, IY]),
+        I::PUSH_qq(qq) => ("PUSH", vec![Reg(qq)]),
+        I::PUSH_IX => ("PUSH", vec![IX]),
+        I::PUSH_IY => ("PUSH", vec![IY]),
+        I::POP_qq(qq) => ("POP", vec![Reg(qq)]),
+        I::POP_IX => ("POP", vec![IX]),
+        I::POP_IY => ("POP", vec![IY]),
+        // Exchange and Transfer
+        I::EX_DE_HL => ("EX", vec![Reg(Register::DE), HL]),
+        I::EX_AF_AF1 => ("EX", vec![Reg(Register::AF), Reg(Register::AF1)]),
+        I::EXX => ("EXX", vec![]),
+        I::EX_SP_HL => ("EX", vec![Text("(SP)"), HL]),
+        I::EX_SP_IX => ("EX", vec![Text("(SP)"), IX]),
+        I::EX_SP_IY => ("EX", vec![Text("(SP)"), IY]),
+        I::LDI => ("LDI", vec![]),
+        I::LDIR => ("LDIR", vec![]),
+        I::LDD => ("LDD", vec![]),
+        I::LDDR => ("LDDR", vec![]),
+        I::CPI => ("CPI", vec![]),
+        I::CPIR => ("CPIR", vec![]),
+        I::CPD => ("CPD", vec![]),
+        I::CPDR => ("CPDR", vec![]),
+        // 8-bit Arithmetic
+        I::ADD_A_r(r) => ("ADD", vec![A, Reg(r)]),
+        I::ADD_A_n(n) => ("ADD", vec![A, Byte(n)]),
+        I::ADD_A_HL => ("ADD", vec![A, MEM_HL]),
+        I::ADD_A_IX(d) => ("ADD", vec![A, ix(d)]),
+        I::ADD_A_IY(d) => ("ADD", vec![A, iy(d)]),
+        I::ADC_A_r(r) => ("ADC", vec![A, Reg(r)]),
+        I::ADC_A_n(n) => ("ADC", vec![A, Byte(n)]),
+        I::ADC_A_HL => ("ADC", vec![A, MEM_HL]),
+        I::ADC_A_IX(d) => ("ADC", vec![A, ix(d)]),
+        I::ADC_A_IY(d) => ("ADC", vec![A, iy(d)]),
+        I::SUB_A_r(r) => ("SUB", vec![Reg(r)]),
+        I::SUB_A_n(n) => ("SUB", vec![Byte(n)]),
+        I::SUB_A_HL => ("SUB", vec![MEM_HL]),
+        I::SUB_A_IX(d) => ("SUB", vec![ix(d)]),
+        I::SUB_A_IY(d) => ("SUB", vec![iy(d)]),
+        I::SBC_A_r(r) => ("SBC", vec![A, Reg(r)]),
+        I::SBC_A_n(n) => ("SBC", vec![A, Byte(n)]),
+        I::SBC_A_HL => ("SBC", vec![A, MEM_HL]),
+        I::SBC_A_IX(d) => ("SBC", vec![A, ix(d)]),
+        I::SBC_A_IY(d) => ("SBC", vec![A, iy(d)]),
+        I::AND_A_r(r) => ("AND", vec![Reg(r)]),
+        I::AND_A_n(n) => ("AND", vec![Byte(n)]),
+        I::AND_A_HL => ("AND", vec![MEM_HL]),
+        I::AND_A_IX(d) => ("AND", vec![ix(d)]),
+        I::AND_A_IY(d) => ("AND", vec![iy(d)]),
+        I::OR_A_r(r) => ("OR", vec![Reg(r)]),
+        I::OR_A_n(n) => ("OR", vec![Byte(n)]),
+        I::OR_A_HL => ("OR", vec![MEM_HL]),
+        I::OR_A_IX(d) => ("OR", vec![ix(d)]),
+        I::OR_A_IY(d) => ("OR", vec![iy(d)]),
+        I::XOR_A_r(r) => ("XOR", vec![Reg(r)]),
+        I::XOR_A_n(n) => ("XOR", vec![Byte(n)]),
+        I::XOR_A_HL => ("XOR", vec![MEM_HL]),
+        I::XOR_A_IX(d) => ("XOR", vec![ix(d)]),
+        I::XOR_A_IY(d) => ("XOR", vec![iy(d)]),
+        I::CP_r(r) => ("CP", vec![Reg(r)]),
+        I::CP_n(n) => ("CP", vec![Byte(n)]),
+        I::CP_HL => ("CP", vec![MEM_HL]),
+        I::CP_IX(d) => ("CP", vec![ix(d)]),
+        I::CP_IY(d) => ("CP", vec![iy(d)]),
+        I::INC_r(r) => ("INC", vec![Reg(r)]),
+        I::INC_HL => ("INC", vec![MEM_HL]),
+        I::INC_IX(d) => ("INC", vec![ix(d)]),
+        I::INC_IY(d) => ("INC", vec![iy(d)]),
+        I::DEC_r(r) => ("DEC", vec![Reg(r)]),
+        I::DEC_HL => ("DEC", vec![MEM_HL]),
+        I::DEC_IX(d) => ("DEC", vec![ix(d)]),
+        I::DEC_IY(d) => ("DEC", vec![iy(d)]),
+        // 16-bit Arithmetic
+        I::ADD_HL_ss(ss) => ("ADD", vec![HL, Reg(ss)]),
+        I::ADC_HL_ss(ss) => ("ADC", vec![HL, Reg(ss)]),
+        I::SBC_HL_ss(ss) => ("SBC", vec![HL, Reg(ss)]),
+        I::ADD_IX_pp(pp) => ("ADD", vec![IX, Reg(pp)]),
+        I::ADD_IY_rr(rr) => ("ADD", vec![IY, Reg(rr)]),
+        I::INC_ss(ss) => ("INC", vec![Reg(ss)]),
+        I::DEC_ss(ss) => ("DEC", vec![Reg(ss)]),
+        // Rotate and Shift
+        I::RLCA => ("RLCA", vec![]),
+        I::RLA => ("RLA", vec![]),
+        I::RRCA => ("RRCA", vec![]),
+        I::RRA => ("RRA", vec![]),
+        I::RLC_r(r) => ("RLC", vec![Reg(r)]),
+        I::RLC_HL => ("RLC", vec![MEM_HL]),
+        I::RLC_IX(d, r) => ("RLC", copy_to(vec![ix(d)], r)),
+        I::RLC_IY(d, r) => ("RLC", copy_to(vec![iy(d)], r)),
+        I::RL_r(r) => ("RL", vec![Reg(r)]),
+        I::RL_HL => ("RL", vec![MEM_HL]),
+        I::RL_IX(d, r) => ("RL", copy_to(vec![ix(d)], r)),
+        I::RL_IY(d, r) => ("RL", copy_to(vec![iy(d)], r)),
+        I::RRC_r(r) => ("RRC", vec![Reg(r)]),
+        I::RRC_HL => ("RRC", vec![MEM_HL]),
+        I::RRC_IX(d, r) => ("RRC", copy_to(vec![ix(d)], r)),
+        I::RRC_IY(d, r) => ("RRC", copy_to(vec![iy(d)], r)),
+        I::RR_r(r) => ("RR", vec![Reg(r)]),
+        I::RR_HL => ("RR", vec![MEM_HL]),
+        I::RR_IX(d, r) => ("RR", copy_to(vec![ix(d)], r)),
+        I::RR_IY(d, r) => ("RR", copy_to(vec![iy(d)], r)),
+        I::SLA_r(r) => ("SLA", vec![Reg(r)]),
+        I::SLA_HL => ("SLA", vec![MEM_HL]),
+        I::SLA_IX(d, r) => ("SLA", copy_to(vec![ix(d)], r)),
+        I::SLA_IY(d, r) => ("SLA", copy_to(vec![iy(d)], r)),
+        I::SRA_r(r) => ("SRA", vec![Reg(r)]),
+        I::SRA_HL => ("SRA", vec![MEM_HL]),
+        I::SRA_IX(d, r) => ("SRA", copy_to(vec![ix(d)], r)),
+        I::SRA_IY(d, r) => ("SRA", copy_to(vec![iy(d)], r)),
+        I::SLL_r(r) => ("SLL", vec![Reg(r)]),
+        I::SLL_HL => ("SLL", vec![MEM_HL]),
+        I::SLL_IX(d, r) => ("SLL", copy_to(vec![ix(d)], r)),
+        I::SLL_IY(d, r) => ("SLL", copy_to(vec![iy(d)], r)),
+        I::SRL_r(r) => ("SRL", vec![Reg(r)]),
+        I::SRL_HL => ("SRL", vec![MEM_HL]),
+        I::SRL_IX(d, r) => ("SRL", copy_to(vec![ix(d)], r)),
+        I::SRL_IY(d, r) => ("SRL", copy_to(vec![iy(d)], r)),
+        I::RLD => ("RLD", vec![]),
+        I::RRD => ("RRD", vec![]),
+        // Bit Set, Reset, and Test
+        I::BIT_b_r(b, r) => ("BIT", vec![Num(b), Reg(r)]),
+        I::BIT_b_HL(b) => ("BIT", vec![Num(b), MEM_HL]),
+        I::BIT_b_IX(b, d) => ("BIT", vec![Num(b), ix(d)]),
+        I::BIT_b_IY(b, d) => ("BIT", vec![Num(b), iy(d)]),
+        I::SET_b_r(b, r) => ("SET", vec![Num(b), Reg(r)]),
+        I::SET_b_HL(b) => ("SET", vec![Num(b), MEM_HL]),
+        I::SET_b_IX(b, d, r) => ("SET", copy_to(vec![Num(b), ix(d)], r)),
+        I::SET_b_IY(b, d, r) => ("SET", copy_to(vec![Num(b), iy(d)], r)),
+        I::RES_b_r(b, r) => ("RES", vec![Num(b), Reg(r)]),
+        I::RES_b_HL(b) => ("RES", vec![Num(b), MEM_HL]),
+        I::RES_b_IX(b, d, r) => ("RES", copy_to(vec![Num(b), ix(d)], r)),
+        I::RES_b_IY(b, d, r) => ("RES", copy_to(vec![Num(b), iy(d)], r)),
+        // General-Purpose Arithmetic and CPU Control
+        I::DAA => ("DAA", vec![]),
+        I::CPL => ("CPL", vec![]),
+        I::NEG => ("NEG", vec![]),
+        I::CCF => ("CCF", vec![]),
+        I::SCF => ("SCF", vec![]),
+        I::NOP => ("NOP", vec![]),
+        I::PREFIX_NOP(prefix) => ("DB", vec![Byte(prefix)]),
+        I::ED_NOP(op) => ("DB", vec![Byte(0xed), Byte(op)]),
+        I::HALT => ("HALT", vec![]),
+        I::DI => ("DI", vec![]),
+        I::EI => ("EI", vec![]),
+        I::IM_0 => ("IM", vec![Num(0)]),
+        I::IM_1 => ("IM", vec![Num(1)]),
+        I::IM_2 => ("IM", vec![Num(2)]),
+        // Jump
+        I::JP_nn(nn) => ("JP", vec![Word(nn)]),
+        I::JP_cc_nn(cc, nn) => ("JP", vec![Cond(cc), Word(nn)]),
+        I::JR_e(e) => ("JR", vec![Rel(e)]),
+        I::JR_C_e(e) => ("JR", vec![Cond(Condition::C), Rel(e)]),
+        I::JR_NC_e(e) => ("JR", vec![Cond(Condition::NC), Rel(e)]),
+        I::JR_Z_e(e) => ("JR", vec![Cond(Condition::Z), Rel(e)]),
+        I::JR_NZ_e(e) => ("JR", vec![Cond(Condition::NZ), Rel(e)]),
+        I::JP_HL => ("JP", vec![MEM_HL]),
+        I::JP_IX => ("JP", vec![Text("(IX)")]),
+        I::JP_IY => ("JP", vec![Text("(IY)")]),
+        I::DJNZ_e(e) => ("DJNZ", vec![Rel(e)]),
+        // Call and Return
+        I::CALL_nn(nn) => ("CALL", vec![Word(nn)]),
+        I::CALL_cc_nn(cc, nn) => ("CALL", vec![Cond(cc), Word(nn)]),
+        I::RET => ("RET", vec![]),
+        I::RET_cc(cc) => ("RET", vec![Cond(cc)]),
+        I::RETI => ("RETI", vec![]),
+        I::RETN => ("RETN", vec![]),
+        I::RST_p(p) => ("RST", vec![Byte(p)]),
+        // Input and Output
+        I::IN_A_n(n) => ("IN", vec![A, Port(n)]),
+        I::IN_r_C(r) => ("IN", vec![Reg(r), PORT_C]),
+        I::IN_C => ("IN", vec![PORT_C]),
+        I::INI => ("INI", vec![]),
+        I::INIR => ("INIR", vec![]),
+        I::IND => ("IND", vec![]),
+        I::INDR => ("INDR", vec![]),
+        I::OUT_n_A(n) => ("OUT", vec![Port(n), A]),
+        I::OUT_C_r(r) => ("OUT", vec![PORT_C, Reg(r)]),
+        I::OUT_C_0 => ("OUT", vec![PORT_C, Text("0")]),
+        I::OUTI => ("OUTI", vec![]),
+        I::OTIR => ("OTIR", vec![]),
+        I::OUTD => ("OUTD", vec![]),
+        I::OTDR => ("OTDR", vec![]),
+    }
+}
+
+/// Returns the name of a register as written in assembly.
+fn register_name(r: Register) -> &'static str {
+    match r {
+        Register::A => "A",
+        Register::F => "F",
+        Register::AF => "AF",
+        Register::B => "B",
+        Register::C => "C",
+        Register::BC => "BC",
+        Register::D => "D",
+        Register::E => "E",
+        Register::DE => "DE",
+        Register::H => "H",
+        Register::L => "L",
+        Register::HL => "HL",
+        Register::IX => "IX",
+        Register::IXH => "IXH",
+        Register::IXL => "IXL",
+        Register::IY => "IY",
+        Register::IYH => "IYH",
+        Register::IYL => "IYL",
+        Register::SP => "SP",
+        Register::PC => "PC",
+        Register::I => "I",
+        Register::R => "R",
+        Register::AF1 => "AF'",
+        Register::BC1 => "BC'",
+        Register::DE1 => "DE'",
+        Register::HL1 => "HL'",
+    }
+}
+
+/// Returns the name of a condition as written in assembly.
+fn condition_name(cc: Condition) -> &'static str {
+    match cc {
+        Condition::NZ => "NZ",
+        Condition::Z => "Z",
+        Condition::NC => "NC",
+        Condition::C => "C",
+        Condition::PO => "PO",
+        Condition::PE => "PE",
+        Condition::P => "P",
+        Condition::M => "M",
+    }
+}
+
+/// Write `val` in hex with the given number of digits.
+fn write_hex(w: &mut impl Write, hex: HexStyle, val: u16, digits: usize) -> fmt::Result {
+    match hex {
+        HexStyle::Suffix => {
+            // A leading zero stops the number being read as a name
+            if (val >> (4 * (digits - 1))) >= 0xa {
+                w.write_char('0')?;
+            }
+            write!(w, "{:01$X}H", val, digits)
+        }
+        HexStyle::Dollar => write!(w, "${:01$X}", val, digits),
+        HexStyle::Prefix => write!(w, "0x{:01$X}", val, digits),
+    }
+}
+
+/// Write a single operand.
+fn write_operand(w: &mut impl Write, hex: HexStyle, op: Operand) -> fmt::Result {
+    match op {
+        Reg(r) => w.write_str(register_name(r)),
+        Text(text) => w.write_str(text),
+        Cond(cc) => w.write_str(condition_name(cc)),
+        Byte(n) => write_hex(w, hex, n as u16, 2),
+        Word(nn) => write_hex(w, hex, nn, 4),
+        Addr(nn) => {
+            w.write_char('(')?;
+            write_hex(w, hex, nn, 4)?;
+            w.write_char(')')
+        }
+        Port(n) => {
+            w.write_char('(')?;
+            write_hex(w, hex, n as u16, 2)?;
+            w.write_char(')')
+        }
+        Index(base, d) => write!(w, "({}{:+})", base, d),
+        // The offset is from the start of the two-byte instruction
+        Rel(e) => write!(w, "${:+}", e as i16 + 2),
+        Num(n) => write!(w, "{}", n),
+    }
+}
+
+/// Write an instruction in upper case Zilog syntax.
+fn write_instruction(w: &mut impl Write, inst: Instruction, hex: HexStyle) -> fmt::Result {
+    let (mnemonic, operands) = parts(inst);
+    w.write_str(mnemonic)?;
+    for (i, op) in operands.into_iter().enumerate() {
+        w.write_char(if i == 0 { ' ' } else { ',' })?;
+        write_operand(w, hex, op)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod disasm_tests {
+    use super::*;
+    use crate::{bus::Ram, Z80};
+    use rstest::*;
+
+    #[rstest]
+    #[case::ld_r_r(&[0x78], "LD A,B")]
+    #[case::ld_r_n(&[0x3e, 0xff], "LD A,0FFH")]
+    #[case::ld_r_hl(&[0x7e], "LD A,(HL)")]
+    #[case::ld_r_ix_neg(&[0xdd, 0x7e, 0xfd], "LD A,(IX-3)")]
+    #[case::ld_iy_n(&[0xfd, 0x36, 0x05, 0x12], "LD (IY+5),12H")]
+    #[case::ld_ixh(&[0xdd, 0x65], "LD IXH,IXL")]
+    #[case::ld_a_nn(&[0x3a, 0x00, 0x50], "LD A,(5000H)")]
+    #[case::ld_nn_a(&[0x32, 0xcd, 0xab], "LD (0ABCDH),A")]
+    #[case::ld_a_bc(&[0x0a], "LD A,(BC)")]
+    #[case::ld_a_r(&[0xed, 0x5f], "LD A,R")]
+    #[case::ld_dd_nn(&[0x31, 0x00, 0x80], "LD SP,8000H")]
+    #[case::ld_dd_inn(&[0xed, 0x4b, 0x34, 0x12], "LD BC,(1234H)")]
+    #[case::ld_nn_ix(&[0xdd, 0x22, 0x34, 0x12], "LD (1234H),IX")]
+    #[case::push_af(&[0xf5], "PUSH AF")]
+    #[case::ex_af(&[0x08], "EX AF,AF'")]
+    #[case::ex_sp_ix(&[0xdd, 0xe3], "EX (SP),IX")]
+    #[case::ldir(&[0xed, 0xb0], "LDIR")]
+    #[case::add_a_r(&[0x80], "ADD A,B")]
+    #[case::sub_n(&[0xd6, 0x01], "SUB 01H")]
+    #[case::xor_a(&[0xaf], "XOR A")]
+    #[case::cp_iy(&[0xfd, 0xbe, 0x00], "CP (IY+0)")]
+    #[case::adc_hl(&[0xed, 0x5a], "ADC HL,DE")]
+    #[case::add_ix(&[0xdd, 0x29], "ADD IX,IX")]
+    #[case::inc_ix(&[0xdd, 0x23], "INC IX")]
+    #[case::rlc_ix(&[0xdd, 0xcb, 0x02, 0x06], "RLC (IX+2)")]
+    #[case::rlc_ix_copy(&[0xdd, 0xcb, 0x02, 0x00], "RLC (IX+2),B")]
+    #[case::bit(&[0xcb, 0x7e], "BIT 7,(HL)")]
+    #[case::set_iy_copy(&[0xfd, 0xcb, 0x80, 0xc7], "SET 0,(IY-128),A")]
+    #[case::im_2(&[0xed, 0x5e], "IM 2")]
+    #[case::jp_cc(&[0xca, 0x00, 0xc0], "JP Z,0C000H")]
+    #[case::jr_back(&[0x18, 0xfe], "JR $+0")]
+    #[case::jr_nz(&[0x20, 0x03], "JR NZ,$+5")]
+    #[case::djnz(&[0x10, 0xf0], "DJNZ $-14")]
+    #[case::jp_ix(&[0xdd, 0xe9], "JP (IX)")]
+    #[case::ret_cc(&[0xd8], "RET C")]
+    #[case::rst(&[0xff], "RST 38H")]
+    #[case::in_a_n(&[0xdb, 0xfe], "IN A,(0FEH)")]
+    #[case::in_c(&[0xed, 0x70], "IN (C)")]
+    #[case::out_c_0(&[0xed, 0x71], "OUT (C),0")]
+    fn test_display(#[case] bytes: &[u8], #[case] expected: &str) {
+        let cpu: Z80 = Default::default();
+        let (inst, _) = cpu.decode(bytes).unwrap();
+        assert_eq!(expected, inst.to_string());
+    }
+
+    #[rstest]
+    #[case::suffix(HexStyle::Suffix, false, "LD (0ABCDH),A", "CP 12H")]
+    #[case::dollar(HexStyle::Dollar, false, "LD ($ABCD),A", "CP $12")]
+    #[case::prefix(HexStyle::Prefix, false, "LD (0xABCD),A", "CP 0x12")]
+    #[case::suffix_lower(HexStyle::Suffix, true, "ld (0abcdh),a", "cp 12h")]
+    #[case::dollar_lower(HexStyle::Dollar, true, "ld ($abcd),a", "cp $12")]
+    #[case::prefix_lower(HexStyle::Prefix, true, "ld (0xabcd),a", "cp 0x12")]
+    fn test_syntax(
+        #[case] hex: HexStyle,
+        #[case] lowercase: bool,
+        #[case] word: &str,
+        #[case] byte: &str,
+    ) {
+        let syntax = Syntax { hex, lowercase };
+        let text = Instruction::LD_nn_A(0xabcd)
+            .display_with(syntax)
+            .to_string();
+        assert_eq!(word, text);
+        let text = Instruction::CP_n(0x12).display_with(syntax).to_string();
+        assert_eq!(byte, text);
+    }
+
+    #[rstest]
+    fn test_display_pads() {
+        assert_eq!("NOP   |", format!("{:<6}|", Instruction::NOP));
+    }
+
+    #[rstest]
+    fn test_disassemble() {
+        // LD HL,0x4000; undefined ED 00; SET 1,(IX+4); truncated JP
+        let code = [
+            0x21, 0x00, 0x40, 0xed, 0x00, 0xdd, 0xcb, 0x04, 0xce, 0xc3, 0x00,
+        ];
+        let mut lines = disassemble(&code, 0xfff8);
+        let line = lines.next().unwrap().unwrap();
+        assert_eq!(0xfff8, line.address);
+        assert_eq!(&[0x21, 0x00, 0x40], line.bytes);
+        assert_eq!(
+            Instruction::LD_dd_nn(Register::HL, 0x4000),
+            line.instruction
+        );
+        let line = lines.next().unwrap().unwrap();
+        assert_eq!(0xfffb, line.address);
+        assert_eq!(&[0xed, 0x00], line.bytes);
+        assert_eq!("DB 0EDH,00H", line.instruction.to_string());
+        let line = lines.next().unwrap().unwrap();
+        assert_eq!(0xfffd, line.address);
+        assert_eq!(4, line.bytes.len());
+        assert_eq!(Instruction::SET_b_IX(1, 4, None), line.instruction);
+        let err = DecodeError::Truncated { address: 0x0001 };
+        assert_eq!(Some(Err(err)), lines.next());
+        assert_eq!(None, lines.next());
+    }
+
+    #[rstest]
+    fn test_disassemble_ignored_prefix() {
+        let lines: Vec<String> = disassemble(&[0xdd, 0x41, 0xfd, 0xfd, 0x00], 0x8000)
+            .map(|line| line.unwrap().instruction.to_string())
+            .collect();
+        assert_eq!(
+            vec!["DB 0DDH", "LD B,C", "DB 0FDH", "DB 0FDH", "NOP"],
+            lines
+        );
+    }
+
+    #[rstest]
+    fn test_disassemble_end_of_memory() {
+        // LD A,0x12 with its operand wrapped round to 0x0000
+        let mut ram = Ram::new();
+        ram[0xffff] = 0x3e;
+        ram[0x0000] = 0x12;
+        let mut lines = disassemble(&ram[0xffff..], 0xffff);
+        let err = DecodeError::Truncated { address: 0xffff };
+        assert_eq!(Some(Err(err)), lines.next());
+        assert_eq!(None, lines.next());
+
+        let mut z80 = Z80 {
+            prog_counter: 0xffff,
+            ..Default::default()
+        };
+        let (instruction, width) = z80.fetch(&mut ram).unwrap();
+        assert_eq!(Instruction::LD_r_n(Register::A, 0x12), instruction);
+        assert_eq!(2, width);
+    }
+
+    #[rstest]
+    fn test_disassemble_empty() {
+        assert_eq!(0, disassemble(&[], 0x8000).count());
+    }
+}
@@ -1,141 +1,643 @@
 //! Methods and macros useful for executing Z80 instructions.
 
-use super::{Flag, Instruction, Z80};
-use std::time::{Duration, Instant};
+use super::{bus::Bus, hi_lo::HiLo, Condition, Flag, Instruction, InterruptMode, Register, Z80};
+mod arith16;
 mod arith8;
+mod bit;
+mod call;
+mod control;
 mod exchange;
+mod io;
 mod jump;
+pub(crate) mod load16;
 mod load8;
+mod rotate;
 
-/// Constrain a function to run for a minimum number of nanoseconds.
-///
-/// This macro requires [`std::time::Duration`] and [`std::time::Instant`] which
-/// should both be used in every module using this macro.
-///
-/// # Examples
-/// These examples use an imagined function `foo` which adds two `u8`s.
-///
-/// Basic example forcing a call to `foo` to last at least 1000ns:
-/// ```
-/// # #[macro_use] extern crate rz80;
-/// # use std::time::{Duration, Instant};
-/// #
-/// # fn foo(x: u8, y: u8) -> u8 { x + y }
-/// # fn main() {
-/// min_time!(foo(2, 3); 1000);
-/// # }
-/// ```
-///
-/// The time limited function can still return its normal return value:
-/// ```
-/// # #[macro_use] extern crate rz80;
-/// # use std::time::{Duration, Instant};
-/// #
-/// # fn foo(x: u8, y: u8) -> u8 { x + y }
-/// # fn main() {
-/// let sum = min_time!(foo(2, 3); 1000);
-/// assert_eq!(5, sum);
-/// # }
-/// ```
-#[macro_export]
-macro_rules! min_time {
-    ($fun:expr; $dur:expr) => {{
-        let d = Duration::from_nanos($dur);
-        let t0 = Instant::now();
-        let x = $fun;
-        while t0.elapsed() < d {
-            // pass
-        }
-        x
+/// Evaluate an expression for its effect on the CPU, then yield the number of T-states
+/// the instruction takes on real hardware.
+macro_rules! cycles {
+    ($fun:expr; $t:expr) => {{
+        $fun;
+        $t
     }};
 }
 
+/// Returns whether an 8-bit value has even parity.
+#[inline]
+fn parity(val: u8) -> bool {
+    val.count_ones().is_multiple_of(2)
+}
+
+/// Copy the undocumented X and Y flags (bits 3 and 5) from the corresponding bits of `val`.
+#[inline]
+fn xy_flags(cpu: &mut Z80, val: u8) {
+    cpu.set_flag(Flag::X, val & Flag::X.mask() != 0);
+    cpu.set_flag(Flag::Y, val & Flag::Y.mask() != 0);
+}
+
+/// Returns the T-states added by the `DD` or `FD` prefix when `r` is half of an index
+/// register.
+#[inline]
+fn prefix_cycles(r: Register) -> u32 {
+    match r {
+        Register::IXH | Register::IXL | Register::IYH | Register::IYL => 4,
+        _ => 0,
+    }
+}
+
 impl Z80 {
-    /// Execute a single instruction.
+    /// Returns the address `base + d` used by the indexed addressing modes, which is
+    /// also latched into MEMPTR.
+    ///
+    /// # Arguments
+    /// - `base`: value of the index register
+    /// - `d`: signed displacement
+    #[inline]
+    fn index_addr(&mut self, base: u16, d: i8) -> u16 {
+        let addr = base.wrapping_add(d as u16);
+        self.memptr = addr;
+        addr
+    }
+
+    /// Returns the byte of memory addressed by `HL`.
+    #[inline]
+    fn mem_hl(&self, bus: &mut impl Bus) -> u8 {
+        bus.read(self.hl)
+    }
+
+    /// Returns the T-states taken by a repeating block instruction, which moves the
+    /// program counter back from `pc` for as long as it repeats.
+    #[inline]
+    fn block_cycles(&self, pc: u16) -> u32 {
+        if self.prog_counter != pc {
+            21
+        } else {
+            16
+        }
+    }
+
+    /// Execute a single instruction, returning the number of T-states it took.
+    ///
+    /// The count includes fetching the instruction and depends on whether a
+    /// conditional branch was taken or a block instruction repeated. It is also added
+    /// to [`Z80::cycles`].
     ///
     /// # Arguments
     /// - `instr`: the instruction to execute
-    /// - `memory`: slice representing the entire memory
-    pub fn execute(&mut self, instr: Instruction, memory: &mut [u8]) {
-        match instr {
+    /// - `bus`: the memory and I/O ports available to the CPU
+    ///
+    /// # Example
+    /// ```
+    /// # use rz80::{bus::Ram, Condition, Instruction, Z80};
+    /// let mut cpu: Z80 = Default::default();
+    /// let mut bus = Ram::new();
+    /// assert_eq!(10, cpu.execute(Instruction::JP_nn(0x8000), &mut bus));
+    /// assert_eq!(5, cpu.execute(Instruction::RET_cc(Condition::C), &mut bus));
+    /// assert_eq!(15, cpu.cycles);
+    /// ```
+    pub fn execute(&mut self, instr: Instruction, bus: &mut impl Bus) -> u32 {
+        self.ei_pending = false;
+        self.prefix_pending = false;
+        let t = match instr {
             // 8-bit load
-            Instruction::LD_r_r(r, r1) => min_time!(load8::load_r_r(self, r, r1); 1000),
-            Instruction::LD_r_n(r, n) => min_time!(load8::load_r_n(self, r, n); 1750),
-            Instruction::LD_r_HL(r) => min_time!(load8::load_r_hl(self, r, memory); 1750),
-            Instruction::LD_r_IX(r, d) => min_time!(load8::load_r_ix(self, r, d, memory); 4750),
-            Instruction::LD_r_IY(r, d) => min_time!(load8::load_r_iy(self, r, d, memory); 4750),
-            Instruction::LD_HL_r(r) => min_time!(load8::load_hl_r(self, r, memory); 1750),
-            Instruction::LD_IX_r(d, r) => min_time!(load8::load_ix_r(self, d, r, memory); 4750),
-            Instruction::LD_IY_r(d, r) => min_time!(load8::load_iy_r(self, d, r, memory); 4750),
-            Instruction::LD_HL_n(n) => min_time!(load8::load_hl_n(self, n, memory); 2500),
-            Instruction::LD_IX_n(d, n) => min_time!(load8::load_ix_n(self, d, n, memory); 4750),
-            Instruction::LD_IY_n(d, n) => min_time!(load8::load_iy_n(self, d, n, memory); 2500),
-            Instruction::LD_A_BC => min_time!(load8::load_a_bc(self, memory); 1750),
-            Instruction::LD_A_DE => min_time!(load8::load_a_de(self, memory); 1750),
-            Instruction::LD_A_nn(nn) => min_time!(load8::load_a_nn(self, nn, memory); 3250),
-            Instruction::LD_BC_A => min_time!(load8::load_bc_a(self, memory); 1750),
-            Instruction::LD_DE_A => min_time!(load8::load_de_a(self, memory); 1750),
-            Instruction::LD_nn_A(nn) => min_time!(load8::load_nn_a(self, nn, memory); 3250),
-            Instruction::LD_A_I => min_time!(load8::load_a_i(self); 2250),
-            Instruction::LD_A_R => min_time!(load8::load_a_r(self); 2250),
-            Instruction::LD_I_A => min_time!(load8::load_i_a(self); 2250),
-            Instruction::LD_R_A => min_time!(load8::load_r_a(self); 2250),
+            Instruction::LD_r_r(r, r1) => {
+                cycles!(load8::load_r_r(self, r, r1); 4 + prefix_cycles(r).max(prefix_cycles(r1)))
+            }
+            Instruction::LD_r_n(r, n) => cycles!(load8::load_r_n(self, r, n); 7 + prefix_cycles(r)),
+            Instruction::LD_r_HL(r) => cycles!(load8::load_r_hl(self, r, bus); 7),
+            Instruction::LD_r_IX(r, d) => cycles!(load8::load_r_ix(self, r, d, bus); 19),
+            Instruction::LD_r_IY(r, d) => cycles!(load8::load_r_iy(self, r, d, bus); 19),
+            Instruction::LD_HL_r(r) => cycles!(load8::load_hl_r(self, r, bus); 7),
+            Instruction::LD_IX_r(d, r) => cycles!(load8::load_ix_r(self, d, r, bus); 19),
+            Instruction::LD_IY_r(d, r) => cycles!(load8::load_iy_r(self, d, r, bus); 19),
+            Instruction::LD_HL_n(n) => cycles!(load8::load_hl_n(self, n, bus); 10),
+            Instruction::LD_IX_n(d, n) => cycles!(load8::load_ix_n(self, d, n, bus); 19),
+            Instruction::LD_IY_n(d, n) => cycles!(load8::load_iy_n(self, d, n, bus); 19),
+            Instruction::LD_A_BC => cycles!(load8::load_a_bc(self, bus); 7),
+            Instruction::LD_A_DE => cycles!(load8::load_a_de(self, bus); 7),
+            Instruction::LD_A_nn(nn) => cycles!(load8::load_a_nn(self, nn, bus); 13),
+            Instruction::LD_BC_A => cycles!(load8::load_bc_a(self, bus); 7),
+            Instruction::LD_DE_A => cycles!(load8::load_de_a(self, bus); 7),
+            Instruction::LD_nn_A(nn) => cycles!(load8::load_nn_a(self, nn, bus); 13),
+            Instruction::LD_A_I => cycles!(load8::load_a_i(self); 9),
+            Instruction::LD_A_R => cycles!(load8::load_a_r(self); 9),
+            Instruction::LD_I_A => cycles!(load8::load_i_a(self); 9),
+            Instruction::LD_R_A => cycles!(load8::load_r_a(self); 9),
+            // 16-bit load
+            Instruction::LD_dd_nn(dd, nn) => cycles!(load16::load_dd_nn(self, dd, nn); 10),
+            Instruction::LD_IX_nn(nn) => cycles!(load16::load_ix_nn(self, nn); 14),
+            Instruction::LD_IY_nn(nn) => cycles!(load16::load_iy_nn(self, nn); 14),
+            Instruction::LD_HL_inn(nn) => {
+                cycles!(load16::load_dd_inn(self, Register::HL, nn, bus); 16)
+            }
+            Instruction::LD_dd_inn(dd, nn) => {
+                cycles!(load16::load_dd_inn(self, dd, nn, bus); 20)
+            }
+            Instruction::LD_IX_inn(nn) => cycles!(load16::load_ix_inn(self, nn, bus); 20),
+            Instruction::LD_IY_inn(nn) => cycles!(load16::load_iy_inn(self, nn, bus); 20),
+            Instruction::LD_nn_HL(nn) => {
+                cycles!(load16::load_nn_dd(self, nn, Register::HL, bus); 16)
+            }
+            Instruction::LD_nn_dd(nn, dd) => {
+                cycles!(load16::load_nn_dd(self, nn, dd, bus); 20)
+            }
+            Instruction::LD_nn_IX(nn) => cycles!(load16::load_nn_ix(self, nn, bus); 20),
+            Instruction::LD_nn_IY(nn) => cycles!(load16::load_nn_iy(self, nn, bus); 20),
+            Instruction::LD_SP_HL => cycles!(load16::load_sp_hl(self); 6),
+            Instruction::LD_SP_IX => cycles!(load16::load_sp_ix(self); 10),
+            Instruction::LD_SP_IY => cycles!(load16::load_sp_iy(self); 10),
+            Instruction::PUSH_qq(qq) => cycles!(load16::push_qq(self, qq, bus); 11),
+            Instruction::PUSH_IX => cycles!(load16::push_ix(self, bus); 15),
+            Instruction::PUSH_IY => cycles!(load16::push_iy(self, bus); 15),
+            Instruction::POP_qq(qq) => cycles!(load16::pop_qq(self, qq, bus); 10),
+            Instruction::POP_IX => cycles!(load16::pop_ix(self, bus); 14),
+            Instruction::POP_IY => cycles!(load16::pop_iy(self, bus); 14),
             // Exchange, Swap, Search
-            Instruction::EX_DE_HL => min_time!(exchange::exchange_de_hl(self); 1000),
-            Instruction::EX_AF_AF1 => min_time!(exchange::exchange_af_af1(self); 1000),
-            Instruction::EXX => min_time!(exchange::exchange_exx(self); 1000),
-            Instruction::EX_SP_HL => min_time!(exchange::exchange_sp_hl(self, memory); 4750),
-            Instruction::EX_SP_IX => min_time!(exchange::exchange_sp_ix(self, memory); 5750),
-            Instruction::EX_SP_IY => min_time!(exchange::exchange_sp_iy(self, memory); 5750),
-            Instruction::LDI => min_time!(exchange::exchange_ldi(self, memory); 4000),
+            Instruction::EX_DE_HL => cycles!(exchange::exchange_de_hl(self); 4),
+            Instruction::EX_AF_AF1 => cycles!(exchange::exchange_af_af1(self); 4),
+            Instruction::EXX => cycles!(exchange::exchange_exx(self); 4),
+            Instruction::EX_SP_HL => cycles!(exchange::exchange_sp_hl(self, bus); 19),
+            Instruction::EX_SP_IX => cycles!(exchange::exchange_sp_ix(self, bus); 23),
+            Instruction::EX_SP_IY => cycles!(exchange::exchange_sp_iy(self, bus); 23),
+            Instruction::LDI => cycles!(exchange::exchange_ldi(self, bus); 16),
             Instruction::LDIR => {
-                if self.bc != 0 {
-                    min_time!(exchange::exchange_ldir(self, memory); 5250)
-                } else {
-                    min_time!(exchange::exchange_ldir(self, memory); 4000)
-                }
+                let pc = self.prog_counter;
+                exchange::exchange_ldir(self, bus);
+                self.block_cycles(pc)
             }
-            Instruction::LDD => min_time!(exchange::exchange_ldd(self, memory); 4000),
+            Instruction::LDD => cycles!(exchange::exchange_ldd(self, bus); 16),
             Instruction::LDDR => {
-                if self.bc != 0 {
-                    min_time!(exchange::exchange_lddr(self, memory); 5250)
-                } else {
-                    min_time!(exchange::exchange_lddr(self, memory); 4000)
-                }
+                let pc = self.prog_counter;
+                exchange::exchange_lddr(self, bus);
+                self.block_cycles(pc)
             }
-            Instruction::CPI => min_time!(exchange::exchange_cpi(self, memory); 4000),
+            Instruction::CPI => cycles!(exchange::exchange_cpi(self, bus); 16),
             Instruction::CPIR => {
-                if self.bc != 0 {
-                    min_time!(exchange::exchange_cpir(self, memory); 5250)
-                } else {
-                    min_time!(exchange::exchange_cpir(self, memory); 4000)
-                }
+                let pc = self.prog_counter;
+                exchange::exchange_cpir(self, bus);
+                self.block_cycles(pc)
             }
-            Instruction::CPD => min_time!(exchange::exchange_cpd(self, memory); 4000),
+            Instruction::CPD => cycles!(exchange::exchange_cpd(self, bus); 16),
             Instruction::CPDR => {
-                if self.bc != 0 {
-                    min_time!(exchange::exchange_cpdr(self, memory); 5250)
-                } else {
-                    min_time!(exchange::exchange_cpdr(self, memory); 4000)
-                }
+                let pc = self.prog_counter;
+                exchange::exchange_cpdr(self, bus);
+                self.block_cycles(pc)
             }
+            // General-Purpose Arithmetic and CPU Control
+            Instruction::DAA => cycles!(control::daa(self); 4),
+            Instruction::CPL => cycles!(control::cpl(self); 4),
+            Instruction::NEG => cycles!(control::neg(self); 8),
+            Instruction::CCF => cycles!(control::ccf(self); 4),
+            Instruction::SCF => cycles!(control::scf(self); 4),
+            Instruction::NOP => 4,
+            Instruction::PREFIX_NOP(_) => cycles!(self.prefix_pending = true; 4),
+            Instruction::ED_NOP(_) => 8,
+            Instruction::HALT => cycles!(control::halt(self); 4),
+            Instruction::DI => cycles!(control::di(self); 4),
+            Instruction::EI => cycles!(control::ei(self); 4),
+            Instruction::IM_0 => cycles!(control::im(self, InterruptMode::IM0); 8),
+            Instruction::IM_1 => cycles!(control::im(self, InterruptMode::IM1); 8),
+            Instruction::IM_2 => cycles!(control::im(self, InterruptMode::IM2); 8),
             // Jump
-            Instruction::JP_nn(nn) => min_time!(jump::jump_nn(self, nn); 2500),
-            Instruction::JP_cc_nn(cc, nn) => min_time!(jump::jump_cc_nn(self, cc, nn); 2500),
-            Instruction::JR_e(e) => min_time!(jump::jumpr_e(self, e); 3000),
-            Instruction::JR_C_e(e) => min_time!(jump::jr_flag_e(self, Flag::C, e); 3000),
-            Instruction::JR_NC_e(e) => min_time!(jump::jr_nflag_e(self, Flag::C, e); 3000),
-            Instruction::JR_Z_e(e) => min_time!(jump::jr_flag_e(self, Flag::Z, e); 3000),
-            Instruction::JR_NZ_e(e) => min_time!(jump::jr_nflag_e(self, Flag::Z, e); 3000),
-            Instruction::JP_HL => min_time!(jump::jump_hl(self); 1000),
-            Instruction::JP_IX => min_time!(jump::jump_ix(self); 1000),
-            Instruction::JP_IY => min_time!(jump::jump_iy(self); 1000),
+            Instruction::JP_nn(nn) => cycles!(jump::jump_nn(self, nn); 10),
+            Instruction::JP_cc_nn(cc, nn) => cycles!(jump::jump_cc_nn(self, cc, nn); 10),
+            Instruction::JR_e(e) => cycles!(jump::jumpr_e(self, e); 12),
+            Instruction::JR_C_e(e) => {
+                let t = if self.condition(Condition::C) { 12 } else { 7 };
+                cycles!(jump::jr_cc_e(self, Condition::C, e); t)
+            }
+            Instruction::JR_NC_e(e) => {
+                let t = if self.condition(Condition::NC) { 12 } else { 7 };
+                cycles!(jump::jr_cc_e(self, Condition::NC, e); t)
+            }
+            Instruction::JR_Z_e(e) => {
+                let t = if self.condition(Condition::Z) { 12 } else { 7 };
+                cycles!(jump::jr_cc_e(self, Condition::Z, e); t)
+            }
+            Instruction::JR_NZ_e(e) => {
+                let t = if self.condition(Condition::NZ) { 12 } else { 7 };
+                cycles!(jump::jr_cc_e(self, Condition::NZ, e); t)
+            }
+            Instruction::JP_HL => cycles!(jump::jump_hl(self); 4),
+            Instruction::JP_IX => cycles!(jump::jump_ix(self); 8),
+            Instruction::JP_IY => cycles!(jump::jump_iy(self); 8),
+            Instruction::DJNZ_e(e) => {
+                let t = if self.bc.hi() != 1 { 13 } else { 8 };
+                cycles!(jump::djnz_e(self, e); t)
+            }
+            // Call and Return
+            Instruction::CALL_nn(nn) => cycles!(call::call_nn(self, nn, bus); 17),
+            Instruction::CALL_cc_nn(cc, nn) => {
+                let t = if self.condition(cc) { 17 } else { 10 };
+                cycles!(call::call_cc_nn(self, cc, nn, bus); t)
+            }
+            Instruction::RET => cycles!(call::ret(self, bus); 10),
+            Instruction::RET_cc(cc) => {
+                let t = if self.condition(cc) { 11 } else { 5 };
+                cycles!(call::ret_cc(self, cc, bus); t)
+            }
+            Instruction::RETI => cycles!(call::reti(self, bus); 14),
+            Instruction::RETN => cycles!(call::retn(self, bus); 14),
+            Instruction::RST_p(p) => cycles!(call::rst_p(self, p, bus); 11),
             // 8-bit Arithmetic
-            Instruction::ADD_A_r(r) => min_time!(arith8::add_a_r(self, r); 1000),
-            Instruction::INC_r(r) => min_time!(arith8::inc_r(self, r); 1000),
-            Instruction::INC_HL => min_time!(arith8::inc_hl(self, memory); 2750),
-            Instruction::DJNZ_e(e) => min_time!(jump::djnz_e(self, e); 3250),
-            _ => todo!("Implement arith8 execution"),
-        }
+            Instruction::ADD_A_r(r) => cycles!(arith8::add_a_r(self, r); 4 + prefix_cycles(r)),
+            Instruction::ADD_A_n(n) => cycles!(arith8::add_a_n(self, n); 7),
+            Instruction::ADD_A_HL => cycles!(arith8::add_a_n(self, self.mem_hl(bus)); 7),
+            Instruction::ADD_A_IX(d) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(arith8::add_a_n(self, bus.read(addr)); 19)
+            }
+            Instruction::ADD_A_IY(d) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(arith8::add_a_n(self, bus.read(addr)); 19)
+            }
+            Instruction::ADC_A_r(r) => {
+                cycles!(arith8::adc_a_n(self, self.reg(r) as u8); 4 + prefix_cycles(r))
+            }
+            Instruction::ADC_A_n(n) => cycles!(arith8::adc_a_n(self, n); 7),
+            Instruction::ADC_A_HL => cycles!(arith8::adc_a_n(self, self.mem_hl(bus)); 7),
+            Instruction::ADC_A_IX(d) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(arith8::adc_a_n(self, bus.read(addr)); 19)
+            }
+            Instruction::ADC_A_IY(d) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(arith8::adc_a_n(self, bus.read(addr)); 19)
+            }
+            Instruction::SUB_A_r(r) => {
+                cycles!(arith8::sub_a_n(self, self.reg(r) as u8); 4 + prefix_cycles(r))
+            }
+            Instruction::SUB_A_n(n) => cycles!(arith8::sub_a_n(self, n); 7),
+            Instruction::SUB_A_HL => cycles!(arith8::sub_a_n(self, self.mem_hl(bus)); 7),
+            Instruction::SUB_A_IX(d) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(arith8::sub_a_n(self, bus.read(addr)); 19)
+            }
+            Instruction::SUB_A_IY(d) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(arith8::sub_a_n(self, bus.read(addr)); 19)
+            }
+            Instruction::SBC_A_r(r) => {
+                cycles!(arith8::sbc_a_n(self, self.reg(r) as u8); 4 + prefix_cycles(r))
+            }
+            Instruction::SBC_A_n(n) => cycles!(arith8::sbc_a_n(self, n); 7),
+            Instruction::SBC_A_HL => cycles!(arith8::sbc_a_n(self, self.mem_hl(bus)); 7),
+            Instruction::SBC_A_IX(d) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(arith8::sbc_a_n(self, bus.read(addr)); 19)
+            }
+            Instruction::SBC_A_IY(d) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(arith8::sbc_a_n(self, bus.read(addr)); 19)
+            }
+            Instruction::AND_A_r(r) => {
+                cycles!(arith8::and_a_n(self, self.reg(r) as u8); 4 + prefix_cycles(r))
+            }
+            Instruction::AND_A_n(n) => cycles!(arith8::and_a_n(self, n); 7),
+            Instruction::AND_A_HL => cycles!(arith8::and_a_n(self, self.mem_hl(bus)); 7),
+            Instruction::AND_A_IX(d) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(arith8::and_a_n(self, bus.read(addr)); 19)
+            }
+            Instruction::AND_A_IY(d) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(arith8::and_a_n(self, bus.read(addr)); 19)
+            }
+            Instruction::OR_A_r(r) => {
+                cycles!(arith8::or_a_n(self, self.reg(r) as u8); 4 + prefix_cycles(r))
+            }
+            Instruction::OR_A_n(n) => cycles!(arith8::or_a_n(self, n); 7),
+            Instruction::OR_A_HL => cycles!(arith8::or_a_n(self, self.mem_hl(bus)); 7),
+            Instruction::OR_A_IX(d) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(arith8::or_a_n(self, bus.read(addr)); 19)
+            }
+            Instruction::OR_A_IY(d) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(arith8::or_a_n(self, bus.read(addr)); 19)
+            }
+            Instruction::XOR_A_r(r) => {
+                cycles!(arith8::xor_a_n(self, self.reg(r) as u8); 4 + prefix_cycles(r))
+            }
+            Instruction::XOR_A_n(n) => cycles!(arith8::xor_a_n(self, n); 7),
+            Instruction::XOR_A_HL => cycles!(arith8::xor_a_n(self, self.mem_hl(bus)); 7),
+            Instruction::XOR_A_IX(d) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(arith8::xor_a_n(self, bus.read(addr)); 19)
+            }
+            Instruction::XOR_A_IY(d) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(arith8::xor_a_n(self, bus.read(addr)); 19)
+            }
+            Instruction::CP_r(r) => {
+                cycles!(arith8::cp_n(self, self.reg(r) as u8); 4 + prefix_cycles(r))
+            }
+            Instruction::CP_n(n) => cycles!(arith8::cp_n(self, n); 7),
+            Instruction::CP_HL => cycles!(arith8::cp_n(self, self.mem_hl(bus)); 7),
+            Instruction::CP_IX(d) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(arith8::cp_n(self, bus.read(addr)); 19)
+            }
+            Instruction::CP_IY(d) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(arith8::cp_n(self, bus.read(addr)); 19)
+            }
+            Instruction::INC_r(r) => cycles!(arith8::inc_r(self, r); 4 + prefix_cycles(r)),
+            Instruction::INC_HL => cycles!(arith8::inc_hl(self, bus); 11),
+            Instruction::INC_IX(d) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(arith8::inc_idx(self, addr, bus); 23)
+            }
+            Instruction::INC_IY(d) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(arith8::inc_idx(self, addr, bus); 23)
+            }
+            Instruction::DEC_r(r) => cycles!(arith8::dec_r(self, r); 4 + prefix_cycles(r)),
+            Instruction::DEC_HL => cycles!(arith8::dec_hl(self, bus); 11),
+            Instruction::DEC_IX(d) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(arith8::dec_idx(self, addr, bus); 23)
+            }
+            Instruction::DEC_IY(d) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(arith8::dec_idx(self, addr, bus); 23)
+            }
+            // 16-bit Arithmetic
+            Instruction::ADD_HL_ss(ss) => cycles!(arith16::add_hl_ss(self, ss); 11),
+            Instruction::ADC_HL_ss(ss) => cycles!(arith16::adc_hl_ss(self, ss); 15),
+            Instruction::SBC_HL_ss(ss) => cycles!(arith16::sbc_hl_ss(self, ss); 15),
+            Instruction::ADD_IX_pp(pp) => cycles!(arith16::add_ix_pp(self, pp); 15),
+            Instruction::ADD_IY_rr(rr) => cycles!(arith16::add_iy_rr(self, rr); 15),
+            Instruction::INC_ss(r @ (Register::IX | Register::IY)) => {
+                cycles!(arith16::inc_ss(self, r); 10)
+            }
+            Instruction::INC_ss(ss) => cycles!(arith16::inc_ss(self, ss); 6),
+            Instruction::DEC_ss(r @ (Register::IX | Register::IY)) => {
+                cycles!(arith16::dec_ss(self, r); 10)
+            }
+            Instruction::DEC_ss(ss) => cycles!(arith16::dec_ss(self, ss); 6),
+            // Rotate and Shift
+            Instruction::RLCA => cycles!(rotate::rlca(self); 4),
+            Instruction::RLA => cycles!(rotate::rla(self); 4),
+            Instruction::RRCA => cycles!(rotate::rrca(self); 4),
+            Instruction::RRA => cycles!(rotate::rra(self); 4),
+            Instruction::RLC_r(r) => cycles!(rotate::shift_r(self, r, rotate::rlc); 8),
+            Instruction::RLC_HL => {
+                cycles!(rotate::shift_m(self, self.hl, None, rotate::rlc, bus); 15)
+            }
+            Instruction::RLC_IX(d, r) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::rlc, bus); 23)
+            }
+            Instruction::RLC_IY(d, r) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::rlc, bus); 23)
+            }
+            Instruction::RRC_r(r) => cycles!(rotate::shift_r(self, r, rotate::rrc); 8),
+            Instruction::RRC_HL => {
+                cycles!(rotate::shift_m(self, self.hl, None, rotate::rrc, bus); 15)
+            }
+            Instruction::RRC_IX(d, r) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::rrc, bus); 23)
+            }
+            Instruction::RRC_IY(d, r) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::rrc, bus); 23)
+            }
+            Instruction::RL_r(r) => cycles!(rotate::shift_r(self, r, rotate::rl); 8),
+            Instruction::RL_HL => {
+                cycles!(rotate::shift_m(self, self.hl, None, rotate::rl, bus); 15)
+            }
+            Instruction::RL_IX(d, r) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::rl, bus); 23)
+            }
+            Instruction::RL_IY(d, r) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::rl, bus); 23)
+            }
+            Instruction::RR_r(r) => cycles!(rotate::shift_r(self, r, rotate::rr); 8),
+            Instruction::RR_HL => {
+                cycles!(rotate::shift_m(self, self.hl, None, rotate::rr, bus); 15)
+            }
+            Instruction::RR_IX(d, r) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::rr, bus); 23)
+            }
+            Instruction::RR_IY(d, r) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::rr, bus); 23)
+            }
+            Instruction::SLA_r(r) => cycles!(rotate::shift_r(self, r, rotate::sla); 8),
+            Instruction::SLA_HL => {
+                cycles!(rotate::shift_m(self, self.hl, None, rotate::sla, bus); 15)
+            }
+            Instruction::SLA_IX(d, r) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::sla, bus); 23)
+            }
+            Instruction::SLA_IY(d, r) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::sla, bus); 23)
+            }
+            Instruction::SRA_r(r) => cycles!(rotate::shift_r(self, r, rotate::sra); 8),
+            Instruction::SRA_HL => {
+                cycles!(rotate::shift_m(self, self.hl, None, rotate::sra, bus); 15)
+            }
+            Instruction::SRA_IX(d, r) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::sra, bus); 23)
+            }
+            Instruction::SRA_IY(d, r) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::sra, bus); 23)
+            }
+            Instruction::SLL_r(r) => cycles!(rotate::shift_r(self, r, rotate::sll); 8),
+            Instruction::SLL_HL => {
+                cycles!(rotate::shift_m(self, self.hl, None, rotate::sll, bus); 15)
+            }
+            Instruction::SLL_IX(d, r) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::sll, bus); 23)
+            }
+            Instruction::SLL_IY(d, r) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::sll, bus); 23)
+            }
+            Instruction::SRL_r(r) => cycles!(rotate::shift_r(self, r, rotate::srl); 8),
+            Instruction::SRL_HL => {
+                cycles!(rotate::shift_m(self, self.hl, None, rotate::srl, bus); 15)
+            }
+            Instruction::SRL_IX(d, r) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::srl, bus); 23)
+            }
+            Instruction::SRL_IY(d, r) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(rotate::shift_m(self, addr, r, rotate::srl, bus); 23)
+            }
+            Instruction::RLD => cycles!(rotate::rld(self, bus); 18),
+            Instruction::RRD => cycles!(rotate::rrd(self, bus); 18),
+            // Bit Set, Reset, and Test
+            Instruction::BIT_b_r(b, r) => cycles!(bit::bit_b(self, b, self.reg(r) as u8); 8),
+            Instruction::BIT_b_HL(b) => cycles!(bit::bit_b_m(self, b, self.mem_hl(bus)); 12),
+            Instruction::BIT_b_IX(b, d) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(bit::bit_b_m(self, b, bus.read(addr)); 20)
+            }
+            Instruction::BIT_b_IY(b, d) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(bit::bit_b_m(self, b, bus.read(addr)); 20)
+            }
+            Instruction::SET_b_r(b, r) => cycles!(bit::set_b_r(self, b, r); 8),
+            Instruction::SET_b_HL(b) => {
+                cycles!(bit::set_b_m(self, b, self.hl, None, bus); 15)
+            }
+            Instruction::SET_b_IX(b, d, r) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(bit::set_b_m(self, b, addr, r, bus); 23)
+            }
+            Instruction::SET_b_IY(b, d, r) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(bit::set_b_m(self, b, addr, r, bus); 23)
+            }
+            Instruction::RES_b_r(b, r) => cycles!(bit::res_b_r(self, b, r); 8),
+            Instruction::RES_b_HL(b) => {
+                cycles!(bit::res_b_m(self, b, self.hl, None, bus); 15)
+            }
+            Instruction::RES_b_IX(b, d, r) => {
+                let addr = self.index_addr(self.index_x, d);
+                cycles!(bit::res_b_m(self, b, addr, r, bus); 23)
+            }
+            Instruction::RES_b_IY(b, d, r) => {
+                let addr = self.index_addr(self.index_y, d);
+                cycles!(bit::res_b_m(self, b, addr, r, bus); 23)
+            }
+            // Input and Output
+            Instruction::IN_A_n(n) => cycles!(io::in_a_n(self, n, bus); 11),
+            Instruction::IN_r_C(r) => cycles!(io::in_r_c(self, r, bus); 12),
+            Instruction::IN_C => cycles!(io::in_c(self, bus); 12),
+            Instruction::INI => cycles!(io::ini(self, bus); 16),
+            Instruction::INIR => {
+                let pc = self.prog_counter;
+                io::inir(self, bus);
+                self.block_cycles(pc)
+            }
+            Instruction::IND => cycles!(io::ind(self, bus); 16),
+            Instruction::INDR => {
+                let pc = self.prog_counter;
+                io::indr(self, bus);
+                self.block_cycles(pc)
+            }
+            Instruction::OUT_n_A(n) => cycles!(io::out_n_a(self, n, bus); 11),
+            Instruction::OUT_C_r(r) => cycles!(io::out_c_r(self, r, bus); 12),
+            Instruction::OUT_C_0 => cycles!(io::out_c_0(self, bus); 12),
+            Instruction::OUTI => cycles!(io::outi(self, bus); 16),
+            Instruction::OTIR => {
+                let pc = self.prog_counter;
+                io::otir(self, bus);
+                self.block_cycles(pc)
+            }
+            Instruction::OUTD => cycles!(io::outd(self, bus); 16),
+            Instruction::OTDR => {
+                let pc = self.prog_counter;
+                io::otdr(self, bus);
+                self.block_cycles(pc)
+            }
+        };
+        self.cycles += t as u64;
+        t
+    }
+}
+
+#[cfg(test)]
+mod execute_tests {
+    use super::*;
+    use crate::bus::Ram;
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[fixture]
+    fn mem() -> Ram {
+        Ram::new()
+    }
+
+    #[rstest]
+    #[case::nop(Instruction::NOP, 4)]
+    #[case::ld_r_r(Instruction::LD_r_r(Register::A, Register::B), 4)]
+    #[case::ld_r_ixh(Instruction::LD_r_r(Register::A, Register::IXH), 8)]
+    #[case::ld_iyl_n(Instruction::LD_r_n(Register::IYL, 0x12), 11)]
+    #[case::add_a_ixl(Instruction::ADD_A_r(Register::IXL), 8)]
+    #[case::inc_iyh(Instruction::INC_r(Register::IYH), 8)]
+    #[case::ld_iy_n(Instruction::LD_IY_n(2, 0x12), 19)]
+    #[case::ld_dd_inn(Instruction::LD_dd_inn(Register::DE, 0x4000), 20)]
+    #[case::ex_sp_ix(Instruction::EX_SP_IX, 23)]
+    #[case::jp_ix(Instruction::JP_IX, 8)]
+    #[case::rst(Instruction::RST_p(0x38), 11)]
+    #[case::set_b_iy(Instruction::SET_b_IY(3, 1, None), 23)]
+    #[case::bit_b_ix(Instruction::BIT_b_IX(3, 1), 20)]
+    #[case::rld(Instruction::RLD, 18)]
+    fn test_cycles(mut z80: Z80, mut mem: Ram, #[case] inst: Instruction, #[case] t: u32) {
+        assert_eq!(t, z80.execute(inst, &mut mem));
+        assert_eq!(t as u64, z80.cycles);
+    }
+
+    #[rstest]
+    #[case::jr_taken(Instruction::JR_Z_e(2), true, 12)]
+    #[case::jr_not_taken(Instruction::JR_Z_e(2), false, 7)]
+    #[case::call_taken(Instruction::CALL_cc_nn(Condition::Z, 0x8000), true, 17)]
+    #[case::call_not_taken(Instruction::CALL_cc_nn(Condition::Z, 0x8000), false, 10)]
+    #[case::ret_taken(Instruction::RET_cc(Condition::Z), true, 11)]
+    #[case::ret_not_taken(Instruction::RET_cc(Condition::Z), false, 5)]
+    #[case::jp_taken(Instruction::JP_cc_nn(Condition::Z, 0x8000), true, 10)]
+    #[case::jp_not_taken(Instruction::JP_cc_nn(Condition::Z, 0x8000), false, 10)]
+    fn test_branch_cycles(
+        mut z80: Z80,
+        mut mem: Ram,
+        #[case] inst: Instruction,
+        #[case] z: bool,
+        #[case] t: u32,
+    ) {
+        z80.set_flag(Flag::Z, z);
+        assert_eq!(t, z80.execute(inst, &mut mem));
+    }
+
+    #[rstest]
+    #[case::taken(0x02, 13)]
+    #[case::not_taken(0x01, 8)]
+    fn test_djnz_cycles(mut z80: Z80, mut mem: Ram, #[case] b: u8, #[case] t: u32) {
+        z80.set_reg(Register::B, b as u16);
+        assert_eq!(t, z80.execute(Instruction::DJNZ_e(-2), &mut mem));
+    }
+
+    #[rstest]
+    #[case::ldir(Instruction::LDIR, 0x0002)]
+    #[case::lddr(Instruction::LDDR, 0x0002)]
+    #[case::otir(Instruction::OTIR, 0x0200)]
+    #[case::indr(Instruction::INDR, 0x0200)]
+    fn test_block_cycles(mut z80: Z80, mut mem: Ram, #[case] inst: Instruction, #[case] bc: u16) {
+        z80.bc = bc;
+        z80.prog_counter = 0x8002;
+        assert_eq!(21, z80.execute(inst, &mut mem));
+        z80.prog_counter = 0x8002;
+        assert_eq!(16, z80.execute(inst, &mut mem));
+        assert_eq!(37, z80.cycles);
+    }
+
+    #[rstest]
+    fn test_cpir_match_cycles(mut z80: Z80, mut mem: Ram) {
+        z80.bc = 0x0002;
+        z80.hl = 0x4000;
+        z80.prog_counter = 0x8002;
+        mem[0x4001] = 0x42;
+        z80.set_reg(Register::A, 0x42);
+        assert_eq!(21, z80.execute(Instruction::CPIR, &mut mem));
+        z80.prog_counter = 0x8002;
+        assert_eq!(16, z80.execute(Instruction::CPIR, &mut mem));
     }
 }
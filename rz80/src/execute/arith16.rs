@@ -0,0 +1,157 @@
+//! Functions for executing 16-bit Arithmetic instructions.
+use super::xy_flags;
+use crate::{
+    carry_borrow::{AddCarry, SubBorrow},
+    Flag, Register, Z80,
+};
+
+/// Add `rhs` to the 16-bit register `dest`, setting only the H, N, C, X, and Y flags.
+#[inline]
+fn add16(cpu: &mut Z80, dest: Register, rhs: Register) {
+    let lhs = cpu.reg(dest);
+    let (sum, carry11, carry15) = lhs.add_carry(cpu.reg(rhs));
+
+    cpu.memptr = lhs.wrapping_add(1);
+    cpu.set_reg(dest, sum);
+    cpu.set_flag(Flag::H, carry11);
+    cpu.set_flag(Flag::N, false);
+    cpu.set_flag(Flag::C, carry15);
+    xy_flags(cpu, (sum >> 8) as u8);
+}
+
+#[inline]
+pub fn add_hl_ss(cpu: &mut Z80, ss: Register) {
+    add16(cpu, Register::HL, ss);
+}
+
+#[inline]
+pub fn add_ix_pp(cpu: &mut Z80, pp: Register) {
+    add16(cpu, Register::IX, pp);
+}
+
+#[inline]
+pub fn add_iy_rr(cpu: &mut Z80, rr: Register) {
+    add16(cpu, Register::IY, rr);
+}
+
+#[inline]
+pub fn adc_hl_ss(cpu: &mut Z80, ss: Register) {
+    let hl = cpu.hl;
+    let rhs = cpu.reg(ss);
+    let (sum, carry11, carry15) = hl.add_carry_in(rhs, cpu.flag(Flag::C));
+
+    cpu.memptr = hl.wrapping_add(1);
+    cpu.hl = sum;
+    cpu.set_flag(Flag::S, (sum as i16) < 0);
+    cpu.set_flag(Flag::Z, sum == 0);
+    cpu.set_flag(Flag::H, carry11);
+    cpu.set_flag(Flag::PV, (hl ^ sum) & (rhs ^ sum) & 0x8000 != 0);
+    cpu.set_flag(Flag::N, false);
+    cpu.set_flag(Flag::C, carry15);
+    xy_flags(cpu, (sum >> 8) as u8);
+}
+
+#[inline]
+pub fn sbc_hl_ss(cpu: &mut Z80, ss: Register) {
+    let hl = cpu.hl;
+    let rhs = cpu.reg(ss);
+    let (diff, borrow12, borrow16) = hl.sub_borrow_in(rhs, cpu.flag(Flag::C));
+
+    cpu.memptr = hl.wrapping_add(1);
+    cpu.hl = diff;
+    cpu.set_flag(Flag::S, (diff as i16) < 0);
+    cpu.set_flag(Flag::Z, diff == 0);
+    cpu.set_flag(Flag::H, borrow12);
+    cpu.set_flag(Flag::PV, (hl ^ rhs) & (hl ^ diff) & 0x8000 != 0);
+    cpu.set_flag(Flag::N, true);
+    cpu.set_flag(Flag::C, borrow16);
+    xy_flags(cpu, (diff >> 8) as u8);
+}
+
+#[inline]
+pub fn inc_ss(cpu: &mut Z80, ss: Register) {
+    let val = cpu.reg(ss).wrapping_add(1);
+    cpu.set_reg(ss, val);
+}
+
+#[inline]
+pub fn dec_ss(cpu: &mut Z80, ss: Register) {
+    let val = cpu.reg(ss).wrapping_sub(1);
+    cpu.set_reg(ss, val);
+}
+
+#[cfg(test)]
+mod arith16_tests {
+    use super::*;
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[rstest]
+    #[case::simple(0x1000, 0x0234, 0x1234)]
+    #[case::wraps(0xffff, 0x0002, 0x0001)]
+    fn test_add_hl_ss(mut z80: Z80, #[case] hl: u16, #[case] de: u16, #[case] expected: u16) {
+        z80.hl = hl;
+        z80.de = de;
+        add_hl_ss(&mut z80, Register::DE);
+        assert_eq!(expected, z80.hl);
+        assert_eq!(de, z80.de);
+        assert_eq!(hl.wrapping_add(1), z80.memptr);
+        assert_eq!(
+            (expected >> 8) as u8 & 0x28,
+            z80.reg(Register::F) as u8 & 0x28
+        );
+    }
+
+    #[rstest]
+    fn test_add_ix_ix(mut z80: Z80) {
+        z80.index_x = 0x4321;
+        add_ix_pp(&mut z80, Register::IX);
+        assert_eq!(0x8642, z80.index_x);
+    }
+
+    #[rstest]
+    fn test_add_iy_sp(mut z80: Z80) {
+        z80.index_y = 0x0100;
+        z80.stack_ptr = 0xff00;
+        add_iy_rr(&mut z80, Register::SP);
+        assert_eq!(0x0000, z80.index_y);
+    }
+
+    #[rstest]
+    #[case::simple(0x1000, 0x0234, 0x1234)]
+    #[case::wraps(0x8000, 0x8000, 0x0000)]
+    fn test_adc_hl_ss(mut z80: Z80, #[case] hl: u16, #[case] bc: u16, #[case] expected: u16) {
+        z80.hl = hl;
+        z80.bc = bc;
+        adc_hl_ss(&mut z80, Register::BC);
+        assert_eq!(expected, z80.hl);
+    }
+
+    #[rstest]
+    #[case::simple(0x9999, 0x1111, 0x8888)]
+    #[case::wraps(0x0000, 0x0001, 0xffff)]
+    #[case::self_zero(0x1234, 0x1234, 0x0000)]
+    fn test_sbc_hl_ss(mut z80: Z80, #[case] hl: u16, #[case] de: u16, #[case] expected: u16) {
+        z80.hl = hl;
+        z80.de = de;
+        sbc_hl_ss(&mut z80, Register::DE);
+        assert_eq!(expected, z80.hl);
+    }
+
+    #[rstest]
+    #[case::bc(Register::BC)]
+    #[case::sp(Register::SP)]
+    #[case::ix(Register::IX)]
+    #[case::iy(Register::IY)]
+    fn test_inc_dec_ss(mut z80: Z80, #[case] ss: Register) {
+        z80.set_reg(ss, 0xffff);
+        inc_ss(&mut z80, ss);
+        assert_eq!(0x0000, z80.reg(ss));
+        dec_ss(&mut z80, ss);
+        assert_eq!(0xffff, z80.reg(ss));
+    }
+}
@@ -1,39 +1,334 @@
-use crate::{carry_borrow::AddCarry, Flag, Register, Z80};
+//! Functions for executing 8-bit Arithmetic and Logic instructions.
+use super::{parity, xy_flags};
+use crate::{
+    bus::Bus,
+    carry_borrow::{AddCarry, SubBorrow},
+    Flag, Register, Z80,
+};
 
+/// Add `n` and optionally the carry flag to the accumulator, setting all flags.
 #[inline]
-pub fn add_a_r(cpu: &mut Z80, r: Register) {
+fn add_a(cpu: &mut Z80, n: u8, carry: bool) {
     let a = cpu.reg(Register::A) as u8;
-    let rval = cpu.reg(r) as u8;
-    let (sum, carry3, carry7) = a.add_carry(rval);
+    let (sum, carry3, carry7) = a.add_carry_in(n, carry);
 
     cpu.set_reg(Register::A, sum as u16);
     cpu.set_flag(Flag::S, (sum as i8) < 0);
     cpu.set_flag(Flag::Z, sum == 0);
     cpu.set_flag(Flag::H, carry3);
-    todo!("Set PV flag");
+    cpu.set_flag(Flag::PV, (a ^ sum) & (n ^ sum) & 0x80 != 0);
     cpu.set_flag(Flag::N, false);
     cpu.set_flag(Flag::C, carry7);
+    xy_flags(cpu, sum);
 }
 
+/// Subtract `n` and optionally the carry flag from the accumulator, setting all
+/// flags. The result is only stored back in `A` if `store` is true; otherwise, as for
+/// `CP`, X and Y are copied from `n` instead of the result.
 #[inline]
-pub fn inc_r(cpu: &mut Z80, r: Register) {
-    let (val, carry3, _) = (cpu.reg(r) as u8).add_carry(1);
-    cpu.set_reg(r, val as u16);
+fn sub_a(cpu: &mut Z80, n: u8, carry: bool, store: bool) {
+    let a = cpu.reg(Register::A) as u8;
+    let (diff, borrow4, borrow8) = a.sub_borrow_in(n, carry);
+
+    if store {
+        cpu.set_reg(Register::A, diff as u16);
+    }
+    cpu.set_flag(Flag::S, (diff as i8) < 0);
+    cpu.set_flag(Flag::Z, diff == 0);
+    cpu.set_flag(Flag::H, borrow4);
+    cpu.set_flag(Flag::PV, (a ^ n) & (a ^ diff) & 0x80 != 0);
+    cpu.set_flag(Flag::N, true);
+    cpu.set_flag(Flag::C, borrow8);
+    xy_flags(cpu, if store { diff } else { n });
+}
+
+/// Store the result of a logical operation in the accumulator and set the flags.
+#[inline]
+fn logic_a(cpu: &mut Z80, val: u8, half: bool) {
+    cpu.set_reg(Register::A, val as u16);
     cpu.set_flag(Flag::S, (val as i8) < 0);
     cpu.set_flag(Flag::Z, val == 0);
-    cpu.set_flag(Flag::H, carry3);
-    cpu.set_flag(Flag::PV, val - 1 == 0x7f);
+    cpu.set_flag(Flag::H, half);
+    cpu.set_flag(Flag::PV, parity(val));
     cpu.set_flag(Flag::N, false);
+    cpu.set_flag(Flag::C, false);
+    xy_flags(cpu, val);
 }
 
+/// Increment a value, setting every flag except carry.
 #[inline]
-pub fn inc_hl(cpu: &mut Z80, memory: &mut [u8]) {
-    let idx = cpu.reg(Register::HL) as usize;
-    let (val, carry3, _) = memory[idx].add_carry(1);
-    memory[idx] = val;
+fn inc(cpu: &mut Z80, old: u8) -> u8 {
+    let (val, carry3, _) = old.add_carry(1);
     cpu.set_flag(Flag::S, (val as i8) < 0);
     cpu.set_flag(Flag::Z, val == 0);
     cpu.set_flag(Flag::H, carry3);
-    cpu.set_flag(Flag::PV, val - 1 == 0x7f);
+    cpu.set_flag(Flag::PV, old == 0x7f);
     cpu.set_flag(Flag::N, false);
+    xy_flags(cpu, val);
+    val
+}
+
+/// Decrement a value, setting every flag except carry.
+#[inline]
+fn dec(cpu: &mut Z80, old: u8) -> u8 {
+    let (val, borrow4) = old.sub_borrow(1);
+    cpu.set_flag(Flag::S, (val as i8) < 0);
+    cpu.set_flag(Flag::Z, val == 0);
+    cpu.set_flag(Flag::H, borrow4);
+    cpu.set_flag(Flag::PV, old == 0x80);
+    cpu.set_flag(Flag::N, true);
+    xy_flags(cpu, val);
+    val
+}
+
+#[inline]
+pub fn add_a_r(cpu: &mut Z80, r: Register) {
+    add_a(cpu, cpu.reg(r) as u8, false);
+}
+
+#[inline]
+pub fn add_a_n(cpu: &mut Z80, n: u8) {
+    add_a(cpu, n, false);
+}
+
+#[inline]
+pub fn adc_a_n(cpu: &mut Z80, n: u8) {
+    add_a(cpu, n, cpu.flag(Flag::C));
+}
+
+#[inline]
+pub fn sub_a_n(cpu: &mut Z80, n: u8) {
+    sub_a(cpu, n, false, true);
+}
+
+#[inline]
+pub fn sbc_a_n(cpu: &mut Z80, n: u8) {
+    sub_a(cpu, n, cpu.flag(Flag::C), true);
+}
+
+#[inline]
+pub fn and_a_n(cpu: &mut Z80, n: u8) {
+    let val = cpu.reg(Register::A) as u8 & n;
+    logic_a(cpu, val, true);
+}
+
+#[inline]
+pub fn or_a_n(cpu: &mut Z80, n: u8) {
+    let val = cpu.reg(Register::A) as u8 | n;
+    logic_a(cpu, val, false);
+}
+
+#[inline]
+pub fn xor_a_n(cpu: &mut Z80, n: u8) {
+    let val = cpu.reg(Register::A) as u8 ^ n;
+    logic_a(cpu, val, false);
+}
+
+#[inline]
+pub fn cp_n(cpu: &mut Z80, n: u8) {
+    sub_a(cpu, n, false, false);
+}
+
+#[inline]
+pub fn inc_r(cpu: &mut Z80, r: Register) {
+    let val = inc(cpu, cpu.reg(r) as u8);
+    cpu.set_reg(r, val as u16);
+}
+
+#[inline]
+pub fn inc_hl(cpu: &mut Z80, bus: &mut impl Bus) {
+    let idx = cpu.reg(Register::HL);
+    let val = inc(cpu, bus.read(idx));
+    bus.write(idx, val);
+}
+
+#[inline]
+pub fn inc_idx(cpu: &mut Z80, addr: u16, bus: &mut impl Bus) {
+    let val = inc(cpu, bus.read(addr));
+    bus.write(addr, val);
+}
+
+#[inline]
+pub fn dec_r(cpu: &mut Z80, r: Register) {
+    let val = dec(cpu, cpu.reg(r) as u8);
+    cpu.set_reg(r, val as u16);
+}
+
+#[inline]
+pub fn dec_hl(cpu: &mut Z80, bus: &mut impl Bus) {
+    let idx = cpu.reg(Register::HL);
+    let val = dec(cpu, bus.read(idx));
+    bus.write(idx, val);
+}
+
+#[inline]
+pub fn dec_idx(cpu: &mut Z80, addr: u16, bus: &mut impl Bus) {
+    let val = dec(cpu, bus.read(addr));
+    bus.write(addr, val);
+}
+
+#[cfg(test)]
+mod arith8_tests {
+    use super::*;
+    use crate::bus::Ram;
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[rstest]
+    #[case::simple(0x12, 0x34, 0x46, false)]
+    #[case::wraps(0xff, 0x02, 0x01, false)]
+    #[case::overflow(0x7f, 0x01, 0x80, true)]
+    #[case::neg_overflow(0x80, 0x80, 0x00, true)]
+    fn test_add_a_n(mut z80: Z80, #[case] a: u8, #[case] n: u8, #[case] sum: u8, #[case] v: bool) {
+        z80.set_reg(Register::A, a as u16);
+        add_a_n(&mut z80, n);
+        assert_eq!(sum as u16, z80.reg(Register::A));
+        assert_eq!(v, z80.flag(Flag::PV));
+        assert!(!z80.flag(Flag::N));
+    }
+
+    #[rstest]
+    #[case::simple(0x12, 0x34, 0x00)]
+    #[case::wraps(0xff, 0x02, 0x11)]
+    #[case::overflow(0x7f, 0x01, 0x94)]
+    #[case::neg_overflow(0x80, 0x80, 0x45)]
+    fn test_add_a_n_flags(mut z80: Z80, #[case] a: u8, #[case] n: u8, #[case] f: u8) {
+        z80.set_reg(Register::A, a as u16);
+        add_a_n(&mut z80, n);
+        assert_eq!(f as u16, z80.reg(Register::F));
+    }
+
+    #[rstest]
+    fn test_adc_a_n_uses_carry(mut z80: Z80) {
+        z80.set_reg(Register::A, 0x0e);
+        z80.set_flag(Flag::C, true);
+        adc_a_n(&mut z80, 0x01);
+        assert_eq!(0x10, z80.reg(Register::A));
+        assert!(z80.flag(Flag::H));
+        assert!(!z80.flag(Flag::C));
+    }
+
+    #[rstest]
+    fn test_add_a_r(mut z80: Z80) {
+        z80.set_reg(Register::A, 0x0f);
+        z80.set_reg(Register::B, 0x01);
+        add_a_r(&mut z80, Register::B);
+        assert_eq!(0x10, z80.reg(Register::A));
+        assert!(z80.flag(Flag::H));
+    }
+
+    #[rstest]
+    #[case::simple(0x46, 0x34, 0x12, false)]
+    #[case::wraps(0x00, 0x01, 0xff, false)]
+    #[case::overflow(0x80, 0x01, 0x7f, true)]
+    #[case::neg_overflow(0x7f, 0xff, 0x80, true)]
+    fn test_sub_a_n(mut z80: Z80, #[case] a: u8, #[case] n: u8, #[case] diff: u8, #[case] v: bool) {
+        z80.set_reg(Register::A, a as u16);
+        sub_a_n(&mut z80, n);
+        assert_eq!(diff as u16, z80.reg(Register::A));
+        assert_eq!(v, z80.flag(Flag::PV));
+        assert!(z80.flag(Flag::N));
+    }
+
+    #[rstest]
+    #[case::simple(0x46, 0x34, 0x02)]
+    #[case::wraps(0x00, 0x01, 0xbb)]
+    #[case::overflow(0x80, 0x01, 0x3e)]
+    #[case::neg_overflow(0x7f, 0xff, 0x87)]
+    fn test_sub_a_n_flags(mut z80: Z80, #[case] a: u8, #[case] n: u8, #[case] f: u8) {
+        z80.set_reg(Register::A, a as u16);
+        sub_a_n(&mut z80, n);
+        assert_eq!(f as u16, z80.reg(Register::F));
+    }
+
+    #[rstest]
+    fn test_sbc_a_n_uses_carry(mut z80: Z80) {
+        z80.set_reg(Register::A, 0x10);
+        z80.set_flag(Flag::C, true);
+        sbc_a_n(&mut z80, 0x0f);
+        assert_eq!(0x00, z80.reg(Register::A));
+        assert!(z80.flag(Flag::Z));
+        assert!(!z80.flag(Flag::C));
+    }
+
+    #[rstest]
+    fn test_cp_n_keeps_a(mut z80: Z80) {
+        z80.set_reg(Register::A, 0x10);
+        cp_n(&mut z80, 0x01);
+        assert_eq!(0x10, z80.reg(Register::A));
+        assert!(z80.flag(Flag::H));
+        assert!(z80.flag(Flag::N));
+    }
+
+    #[rstest]
+    #[case::add(add_a_n as fn(&mut Z80, u8), 0x00, 0x28)]
+    #[case::sub(sub_a_n as fn(&mut Z80, u8), 0x28, 0x00)]
+    #[case::cp(cp_n as fn(&mut Z80, u8), 0x28, 0x28)]
+    #[case::xor(xor_a_n as fn(&mut Z80, u8), 0x00, 0x28)]
+    fn test_xy_flags(mut z80: Z80, #[case] op: fn(&mut Z80, u8), #[case] a: u8, #[case] xy: u8) {
+        z80.set_reg(Register::A, a as u16);
+        op(&mut z80, 0x28);
+        assert_eq!(xy, z80.reg(Register::F) as u8 & 0x28);
+    }
+
+    #[rstest]
+    #[case::and(and_a_n as fn(&mut Z80, u8), 0x0a, true)]
+    #[case::or(or_a_n as fn(&mut Z80, u8), 0xfe, false)]
+    #[case::xor(xor_a_n as fn(&mut Z80, u8), 0xf4, false)]
+    fn test_logic_a_n(
+        mut z80: Z80,
+        #[case] op: fn(&mut Z80, u8),
+        #[case] expected: u8,
+        #[case] half: bool,
+    ) {
+        z80.set_reg(Register::A, 0x5e);
+        op(&mut z80, 0xaa);
+        assert_eq!(expected as u16, z80.reg(Register::A));
+        assert_eq!(half, z80.flag(Flag::H));
+        assert_eq!(parity(expected), z80.flag(Flag::PV));
+        assert!(!z80.flag(Flag::N));
+        assert!(!z80.flag(Flag::C));
+        assert_eq!(expected >= 0x80, z80.flag(Flag::S));
+    }
+
+    #[rstest]
+    #[case::simple(0x01, 0x02, false)]
+    #[case::wraps(0xff, 0x00, false)]
+    #[case::overflow(0x7f, 0x80, true)]
+    fn test_inc_r(mut z80: Z80, #[case] old: u8, #[case] new: u8, #[case] v: bool) {
+        z80.set_reg(Register::D, old as u16);
+        inc_r(&mut z80, Register::D);
+        assert_eq!(new as u16, z80.reg(Register::D));
+        assert_eq!(v, z80.flag(Flag::PV));
+        assert_eq!(new == 0, z80.flag(Flag::Z));
+        assert_eq!(new >= 0x80, z80.flag(Flag::S));
+    }
+
+    #[rstest]
+    #[case::simple(0x02, 0x01, false)]
+    #[case::wraps(0x00, 0xff, false)]
+    #[case::overflow(0x80, 0x7f, true)]
+    fn test_dec_hl(mut z80: Z80, #[case] old: u8, #[case] new: u8, #[case] v: bool) {
+        let mut mem = Ram::new();
+        mem[2] = old;
+        z80.hl = 2;
+        dec_hl(&mut z80, &mut mem);
+        assert_eq!(new, mem[2]);
+        assert_eq!(v, z80.flag(Flag::PV));
+        assert!(z80.flag(Flag::N));
+    }
+
+    #[rstest]
+    fn test_inc_dec_idx(mut z80: Z80) {
+        let mut mem = Ram::new();
+        mem[..4].fill(0x41);
+        inc_idx(&mut z80, 3, &mut mem);
+        assert_eq!(0x42, mem[3]);
+        dec_idx(&mut z80, 1, &mut mem);
+        assert_eq!(0x40, mem[1]);
+    }
 }
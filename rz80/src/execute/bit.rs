@@ -0,0 +1,115 @@
+//! Functions for executing Bit Set, Reset, and Test instructions.
+use super::xy_flags;
+use crate::{bus::Bus, hi_lo::HiLo, Flag, Register, Z80};
+
+/// Test bit `b` of `val`.
+///
+/// Z is set if the bit is 0. PV mirrors Z, and S is set only when testing a set
+/// bit 7, matching real hardware where the documentation leaves them unspecified.
+/// X and Y are copied from `val`.
+#[inline]
+pub fn bit_b(cpu: &mut Z80, b: u8, val: u8) {
+    let set = val & (1 << b) != 0;
+    cpu.set_flag(Flag::S, b == 7 && set);
+    cpu.set_flag(Flag::Z, !set);
+    cpu.set_flag(Flag::H, true);
+    cpu.set_flag(Flag::PV, !set);
+    cpu.set_flag(Flag::N, false);
+    xy_flags(cpu, val);
+}
+
+/// Test bit `b` of a byte of memory.
+///
+/// This is the same as [`bit_b`] except that X and Y leak from the high byte of
+/// MEMPTR rather than from the tested value.
+#[inline]
+pub fn bit_b_m(cpu: &mut Z80, b: u8, val: u8) {
+    bit_b(cpu, b, val);
+    xy_flags(cpu, cpu.memptr.hi());
+}
+
+#[inline]
+pub fn set_b_r(cpu: &mut Z80, b: u8, r: Register) {
+    let val = cpu.reg(r) as u8 | (1 << b);
+    cpu.set_reg(r, val as u16);
+}
+
+#[inline]
+pub fn res_b_r(cpu: &mut Z80, b: u8, r: Register) {
+    let val = cpu.reg(r) as u8 & !(1 << b);
+    cpu.set_reg(r, val as u16);
+}
+
+/// Set bit `b` of a byte of memory, also copying the result into `wb` if it is given.
+#[inline]
+pub fn set_b_m(cpu: &mut Z80, b: u8, addr: u16, wb: Option<Register>, bus: &mut impl Bus) {
+    let val = bus.read(addr) | 1 << b;
+    bus.write(addr, val);
+    if let Some(r) = wb {
+        cpu.set_reg(r, val as u16);
+    }
+}
+
+/// Reset bit `b` of a byte of memory, also copying the result into `wb` if it is given.
+#[inline]
+pub fn res_b_m(cpu: &mut Z80, b: u8, addr: u16, wb: Option<Register>, bus: &mut impl Bus) {
+    let val = bus.read(addr) & !(1 << b);
+    bus.write(addr, val);
+    if let Some(r) = wb {
+        cpu.set_reg(r, val as u16);
+    }
+}
+
+#[cfg(test)]
+mod bit_tests {
+    use super::*;
+    use crate::bus::Ram;
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[rstest]
+    #[case::set(0b0000_1000, 3, false)]
+    #[case::clear(0b1111_0111, 3, true)]
+    fn test_bit_b(mut z80: Z80, #[case] val: u8, #[case] b: u8, #[case] zero: bool) {
+        bit_b(&mut z80, b, val);
+        assert_eq!(zero, z80.flag(Flag::PV));
+        assert!(z80.flag(Flag::H));
+        assert!(!z80.flag(Flag::N));
+    }
+
+    #[rstest]
+    fn test_bit_b_xy(mut z80: Z80) {
+        bit_b(&mut z80, 0, 0x28);
+        assert_eq!(0x28, z80.reg(Register::F) as u8 & 0x28);
+        z80.memptr = 0x0800;
+        bit_b_m(&mut z80, 0, 0x28);
+        assert_eq!(0x08, z80.reg(Register::F) as u8 & 0x28);
+    }
+
+    #[rstest]
+    #[case::b0(0, 0x01)]
+    #[case::b4(4, 0x10)]
+    #[case::b7(7, 0x80)]
+    fn test_set_res_b_r(mut z80: Z80, #[case] b: u8, #[case] mask: u8) {
+        set_b_r(&mut z80, b, Register::C);
+        assert_eq!(mask as u16, z80.reg(Register::C));
+        z80.set_reg(Register::C, 0xff);
+        res_b_r(&mut z80, b, Register::C);
+        assert_eq!(!mask as u16, z80.reg(Register::C));
+    }
+
+    #[rstest]
+    fn test_set_res_b_m(mut z80: Z80) {
+        let mut mem = Ram::new();
+        mem[..2].copy_from_slice(&[0x00, 0xff]);
+        set_b_m(&mut z80, 6, 0, None, &mut mem);
+        assert_eq!(0x40, mem[0]);
+        res_b_m(&mut z80, 0, 1, Some(Register::H), &mut mem);
+        assert_eq!(0xfe, mem[1]);
+        assert_eq!(0xfe, z80.reg(Register::H));
+    }
+}
@@ -0,0 +1,140 @@
+//! Functions for executing Call and Return instructions.
+use super::load16::{pop, push};
+use crate::{bus::Bus, Condition, Z80};
+
+#[inline]
+pub fn call_nn(cpu: &mut Z80, nn: u16, bus: &mut impl Bus) {
+    push(cpu, cpu.prog_counter, bus);
+    cpu.prog_counter = nn;
+    cpu.memptr = nn;
+}
+
+/// Call `nn` if `cc` holds. MEMPTR is set to `nn` whether or not the call is made.
+#[inline]
+pub fn call_cc_nn(cpu: &mut Z80, cc: Condition, nn: u16, bus: &mut impl Bus) {
+    cpu.memptr = nn;
+    if cpu.condition(cc) {
+        call_nn(cpu, nn, bus);
+    }
+}
+
+#[inline]
+pub fn ret(cpu: &mut Z80, bus: &mut impl Bus) {
+    cpu.prog_counter = pop(cpu, bus);
+    cpu.memptr = cpu.prog_counter;
+}
+
+#[inline]
+pub fn ret_cc(cpu: &mut Z80, cc: Condition, bus: &mut impl Bus) {
+    if cpu.condition(cc) {
+        ret(cpu, bus);
+    }
+}
+
+/// Return from an interrupt. Like `RETN`, this restores `IFF1` from `IFF2`.
+#[inline]
+pub fn reti(cpu: &mut Z80, bus: &mut impl Bus) {
+    cpu.iff1 = cpu.iff2;
+    ret(cpu, bus);
+}
+
+/// Return from a non-maskable interrupt, restoring `IFF1` from `IFF2`.
+#[inline]
+pub fn retn(cpu: &mut Z80, bus: &mut impl Bus) {
+    cpu.iff1 = cpu.iff2;
+    ret(cpu, bus);
+}
+
+#[inline]
+pub fn rst_p(cpu: &mut Z80, p: u8, bus: &mut impl Bus) {
+    call_nn(cpu, p as u16, bus);
+}
+
+#[cfg(test)]
+mod call_tests {
+    use super::*;
+    use crate::bus::Ram;
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[fixture]
+    fn mem() -> Ram {
+        Ram::new()
+    }
+
+    #[rstest]
+    fn test_call_nn(mut z80: Z80, mut mem: Ram) {
+        z80.prog_counter = 0x1234;
+        z80.stack_ptr = 0x8000;
+        call_nn(&mut z80, 0x4000, &mut mem);
+        assert_eq!(0x4000, z80.prog_counter);
+        assert_eq!(0x7ffe, z80.stack_ptr);
+        assert_eq!(0x12, mem[0x7fff]);
+        assert_eq!(0x34, mem[0x7ffe]);
+    }
+
+    #[rstest]
+    fn test_call_ret_wraps(mut z80: Z80, mut mem: Ram) {
+        z80.prog_counter = 0xabcd;
+        z80.stack_ptr = 0x0001;
+        call_nn(&mut z80, 0x0100, &mut mem);
+        assert_eq!(0xffff, z80.stack_ptr);
+        ret(&mut z80, &mut mem);
+        assert_eq!(0xabcd, z80.prog_counter);
+        assert_eq!(0x0001, z80.stack_ptr);
+        assert_eq!(0xabcd, z80.memptr);
+    }
+
+    #[rstest]
+    fn test_call_cc_not_taken_sets_memptr(mut z80: Z80, mut mem: Ram) {
+        z80.prog_counter = 0x1234;
+        z80.stack_ptr = 0x8000;
+        call_cc_nn(&mut z80, Condition::Z, 0x4000, &mut mem);
+        assert_eq!(0x1234, z80.prog_counter);
+        assert_eq!(0x4000, z80.memptr);
+    }
+
+    #[rstest]
+    fn test_retn_restores_iff1(mut z80: Z80, mut mem: Ram) {
+        z80.stack_ptr = 0x8000;
+        push(&mut z80, 0x1234, &mut mem);
+        z80.iff1 = false;
+        z80.iff2 = true;
+        retn(&mut z80, &mut mem);
+        assert_eq!(0x1234, z80.prog_counter);
+        assert!(z80.iff1);
+    }
+
+    #[rstest]
+    fn test_reti(mut z80: Z80, mut mem: Ram) {
+        z80.stack_ptr = 0x8000;
+        push(&mut z80, 0x5678, &mut mem);
+        z80.iff2 = true;
+        reti(&mut z80, &mut mem);
+        assert_eq!(0x5678, z80.prog_counter);
+        assert_eq!(0x8000, z80.stack_ptr);
+        assert!(z80.iff1);
+    }
+
+    #[rstest]
+    #[case::rst_00(0x00)]
+    #[case::rst_08(0x08)]
+    #[case::rst_10(0x10)]
+    #[case::rst_18(0x18)]
+    #[case::rst_20(0x20)]
+    #[case::rst_28(0x28)]
+    #[case::rst_30(0x30)]
+    #[case::rst_38(0x38)]
+    fn test_rst_p(mut z80: Z80, mut mem: Ram, #[case] p: u8) {
+        z80.prog_counter = 0x1235;
+        z80.stack_ptr = 0x8000;
+        rst_p(&mut z80, p, &mut mem);
+        assert_eq!(p as u16, z80.prog_counter);
+        assert_eq!(0x35, mem[0x7ffe]);
+        assert_eq!(0x12, mem[0x7fff]);
+    }
+}
@@ -0,0 +1,227 @@
+//! Functions for executing General-Purpose Arithmetic and CPU Control instructions.
+use super::{parity, xy_flags};
+use crate::{carry_borrow::SubBorrow, Flag, InterruptMode, Register, Z80};
+
+/// Adjust the accumulator to packed BCD after an addition or subtraction.
+#[inline]
+pub fn daa(cpu: &mut Z80) {
+    let a = cpu.reg(Register::A) as u8;
+    let lo = a & 0x0f;
+    let n = cpu.flag(Flag::N);
+
+    let mut diff = 0;
+    if cpu.flag(Flag::H) || lo > 9 {
+        diff |= 0x06;
+    }
+    let carry = cpu.flag(Flag::C) || a > 0x99;
+    if carry {
+        diff |= 0x60;
+    }
+
+    let res = if n {
+        a.wrapping_sub(diff)
+    } else {
+        a.wrapping_add(diff)
+    };
+    let half = if n {
+        cpu.flag(Flag::H) && lo < 6
+    } else {
+        lo > 9
+    };
+
+    cpu.set_reg(Register::A, res as u16);
+    cpu.set_flag(Flag::S, (res as i8) < 0);
+    cpu.set_flag(Flag::Z, res == 0);
+    cpu.set_flag(Flag::H, half);
+    cpu.set_flag(Flag::PV, parity(res));
+    cpu.set_flag(Flag::C, carry);
+    xy_flags(cpu, res);
+}
+
+#[inline]
+pub fn cpl(cpu: &mut Z80) {
+    let a = cpu.reg(Register::A) as u8;
+    cpu.set_reg(Register::A, !a as u16);
+    cpu.set_flag(Flag::H, true);
+    cpu.set_flag(Flag::N, true);
+    xy_flags(cpu, !a);
+}
+
+#[inline]
+pub fn neg(cpu: &mut Z80) {
+    let a = cpu.reg(Register::A) as u8;
+    let (res, borrow4, borrow8) = 0u8.sub_borrow_in(a, false);
+
+    cpu.set_reg(Register::A, res as u16);
+    cpu.set_flag(Flag::S, (res as i8) < 0);
+    cpu.set_flag(Flag::Z, res == 0);
+    cpu.set_flag(Flag::H, borrow4);
+    cpu.set_flag(Flag::PV, a == 0x80);
+    cpu.set_flag(Flag::N, true);
+    cpu.set_flag(Flag::C, borrow8);
+    xy_flags(cpu, res);
+}
+
+/// Complement the carry flag, with X and Y copied from the accumulator.
+#[inline]
+pub fn ccf(cpu: &mut Z80) {
+    let c = cpu.flag(Flag::C);
+    cpu.set_flag(Flag::H, c);
+    cpu.set_flag(Flag::N, false);
+    cpu.set_flag(Flag::C, !c);
+    xy_flags(cpu, cpu.reg(Register::A) as u8);
+}
+
+/// Set the carry flag, with X and Y copied from the accumulator.
+#[inline]
+pub fn scf(cpu: &mut Z80) {
+    cpu.set_flag(Flag::H, false);
+    cpu.set_flag(Flag::N, false);
+    cpu.set_flag(Flag::C, true);
+    xy_flags(cpu, cpu.reg(Register::A) as u8);
+}
+
+/// Halt the CPU until the next interrupt.
+///
+/// The program counter is left pointing at the `HALT` so that it is executed again,
+/// as a `NOP`, on every following cycle.
+#[inline]
+pub fn halt(cpu: &mut Z80) {
+    cpu.halted = true;
+    cpu.prog_counter = cpu.prog_counter.wrapping_sub(1);
+}
+
+#[inline]
+pub fn di(cpu: &mut Z80) {
+    cpu.iff1 = false;
+    cpu.iff2 = false;
+}
+
+/// Enable maskable interrupts.
+///
+/// Interrupts are not accepted until the instruction following `EI` has executed.
+#[inline]
+pub fn ei(cpu: &mut Z80) {
+    cpu.iff1 = true;
+    cpu.iff2 = true;
+    cpu.ei_pending = true;
+}
+
+#[inline]
+pub fn im(cpu: &mut Z80, mode: InterruptMode) {
+    cpu.interrupt_mode = mode;
+}
+
+#[cfg(test)]
+mod control_tests {
+    use super::*;
+    use crate::execute::arith8::{add_a_n, sub_a_n};
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[rstest]
+    #[case::no_adjust(0x12, 0x34, 0x46)]
+    #[case::lo_adjust(0x15, 0x27, 0x42)]
+    #[case::half_carry(0x19, 0x28, 0x47)]
+    fn test_daa_add(mut z80: Z80, #[case] a: u8, #[case] n: u8, #[case] expected: u8) {
+        z80.set_reg(Register::A, a as u16);
+        add_a_n(&mut z80, n);
+        daa(&mut z80);
+        assert_eq!(expected as u16, z80.reg(Register::A));
+    }
+
+    #[rstest]
+    #[case::no_adjust(0x46, 0x34, 0x12)]
+    #[case::half_borrow(0x42, 0x15, 0x27)]
+    fn test_daa_sub(mut z80: Z80, #[case] a: u8, #[case] n: u8, #[case] expected: u8) {
+        z80.set_reg(Register::A, a as u16);
+        sub_a_n(&mut z80, n);
+        daa(&mut z80);
+        assert_eq!(expected as u16, z80.reg(Register::A));
+        assert!(z80.flag(Flag::N));
+        assert!(!z80.flag(Flag::C));
+    }
+
+    #[rstest]
+    fn test_daa_carry(mut z80: Z80) {
+        z80.set_reg(Register::A, 0x99);
+        add_a_n(&mut z80, 0x01);
+        daa(&mut z80);
+        assert_eq!(0x00, z80.reg(Register::A));
+        assert!(z80.flag(Flag::Z));
+        assert!(z80.flag(Flag::C));
+    }
+
+    #[rstest]
+    fn test_cpl(mut z80: Z80) {
+        z80.set_reg(Register::A, 0xb4);
+        cpl(&mut z80);
+        assert_eq!(0x4b, z80.reg(Register::A));
+        assert!(z80.flag(Flag::H));
+        assert!(z80.flag(Flag::N));
+        assert_eq!(0x08, z80.reg(Register::F) as u8 & 0x28);
+    }
+
+    #[rstest]
+    #[case::one(0x01, 0xff, false)]
+    #[case::zero(0x00, 0x00, false)]
+    #[case::min(0x80, 0x80, true)]
+    fn test_neg(mut z80: Z80, #[case] a: u8, #[case] expected: u8, #[case] v: bool) {
+        z80.set_reg(Register::A, a as u16);
+        neg(&mut z80);
+        assert_eq!(expected as u16, z80.reg(Register::A));
+        assert_eq!(v, z80.flag(Flag::PV));
+        assert!(z80.flag(Flag::N));
+        assert_eq!(a != 0, z80.flag(Flag::C));
+        assert_eq!(a == 0, z80.flag(Flag::Z));
+        assert_eq!(expected >= 0x80, z80.flag(Flag::S));
+    }
+
+    #[rstest]
+    fn test_scf_ccf(mut z80: Z80) {
+        scf(&mut z80);
+        assert!(z80.flag(Flag::C));
+        ccf(&mut z80);
+        assert!(!z80.flag(Flag::C));
+        assert!(z80.flag(Flag::H));
+        ccf(&mut z80);
+        assert!(z80.flag(Flag::C));
+        assert!(!z80.flag(Flag::H));
+    }
+
+    #[rstest]
+    fn test_scf_xy(mut z80: Z80) {
+        z80.set_reg(Register::A, 0x28);
+        scf(&mut z80);
+        assert_eq!(0x28, z80.reg(Register::F) as u8 & 0x28);
+    }
+
+    #[rstest]
+    fn test_halt(mut z80: Z80) {
+        z80.prog_counter = 0x1235;
+        halt(&mut z80);
+        assert!(z80.halted);
+        assert_eq!(0x1234, z80.prog_counter);
+    }
+
+    #[rstest]
+    fn test_di_ei(mut z80: Z80) {
+        ei(&mut z80);
+        assert!(z80.iff1 && z80.iff2);
+        di(&mut z80);
+        assert!(!z80.iff1 && !z80.iff2);
+    }
+
+    #[rstest]
+    #[case::im0(InterruptMode::IM0)]
+    #[case::im1(InterruptMode::IM1)]
+    #[case::im2(InterruptMode::IM2)]
+    fn test_im(mut z80: Z80, #[case] mode: InterruptMode) {
+        im(&mut z80, mode);
+        assert_eq!(mode, z80.interrupt_mode);
+    }
+}
@@ -1,5 +1,6 @@
 //! Functions for executing Exchange instructions.
-use crate::{hi_lo::HiLo, Flag, Register, Z80};
+use super::{load16::read_word, xy_flags};
+use crate::{bus::Bus, carry_borrow::SubBorrow, hi_lo::HiLo, Flag, Register, Z80};
 use std::mem::swap;
 
 #[inline]
@@ -19,154 +20,513 @@ pub fn exchange_exx(cpu: &mut Z80) {
     swap(&mut cpu.hl, &mut cpu.hl1);
 }
 
+/// Swap a register with the word on top of the stack, returning the word.
+///
+/// The stack pointer wraps around at the top of the address space, and MEMPTR is set
+/// to the word read. As on hardware, the high byte is written before the low byte.
 #[inline]
-pub fn exchange_sp_hl(cpu: &mut Z80, mem: &[u8]) {
-    let lo = mem[cpu.stack_ptr as usize];
-    cpu.set_reg(Register::L, lo as u16);
-    let hi = mem[(cpu.stack_ptr + 1) as usize];
-    cpu.set_reg(Register::H, hi as u16);
+fn exchange_sp(cpu: &mut Z80, val: u16, bus: &mut impl Bus) -> u16 {
+    let sp = cpu.stack_ptr;
+    let word = read_word(bus, sp);
+    bus.write(sp.wrapping_add(1), val.hi());
+    bus.write(sp, val.lo());
+    cpu.memptr = word;
+    word
 }
 
 #[inline]
-pub fn exchange_sp_ix(cpu: &mut Z80, mem: &[u8]) {
-    let lo = mem[cpu.stack_ptr as usize];
-    cpu.index_x.set_lo(lo);
-    let hi = mem[(cpu.stack_ptr + 1) as usize];
-    cpu.index_x.set_hi(hi);
+pub fn exchange_sp_hl(cpu: &mut Z80, bus: &mut impl Bus) {
+    cpu.hl = exchange_sp(cpu, cpu.hl, bus);
 }
 
 #[inline]
-pub fn exchange_sp_iy(cpu: &mut Z80, mem: &[u8]) {
-    let lo = mem[cpu.stack_ptr as usize];
-    cpu.index_y.set_lo(lo);
-    let hi = mem[(cpu.stack_ptr + 1) as usize];
-    cpu.index_y.set_hi(hi);
+pub fn exchange_sp_ix(cpu: &mut Z80, bus: &mut impl Bus) {
+    cpu.index_x = exchange_sp(cpu, cpu.index_x, bus);
 }
 
 #[inline]
-pub fn exchange_ldi(cpu: &mut Z80, mem: &mut [u8]) {
-    let src = cpu.hl as usize;
-    let dest = cpu.de as usize;
-    mem[dest] = mem[src];
-    cpu.de += 1;
-    cpu.hl += 1;
-    cpu.bc -= 1;
-
-    cpu.set_flag(Flag::H, false);
-    cpu.set_flag(Flag::PV, cpu.bc - 1 != 0);
-    cpu.set_flag(Flag::N, false);
+pub fn exchange_sp_iy(cpu: &mut Z80, bus: &mut impl Bus) {
+    cpu.index_y = exchange_sp(cpu, cpu.index_y, bus);
 }
 
+/// Set the undocumented flags of a block transfer or search, which copy `Y` from bit 1
+/// of `n` and `X` from bit 3.
 #[inline]
-pub fn exchange_ldir(cpu: &mut Z80, mem: &mut [u8]) {
-    let src = cpu.hl as usize;
-    let dest = cpu.de as usize;
-    mem[dest] = mem[src];
-    cpu.de += 1;
-    cpu.hl += 1;
-    cpu.bc -= 1;
-
-    cpu.set_flag(Flag::H, false);
-    cpu.set_flag(Flag::PV, cpu.bc - 1 != 0);
-    cpu.set_flag(Flag::N, false);
+fn block_xy_flags(cpu: &mut Z80, n: u8) {
+    xy_flags(cpu, (n & 0x08) | ((n & 0x02) << 4));
+}
 
-    if cpu.bc != 0 {
-        cpu.prog_counter -= 2;
-    }
+/// Move the program counter back to a block instruction so that it runs again.
+#[inline]
+fn block_repeat(cpu: &mut Z80) {
+    cpu.prog_counter = cpu.prog_counter.wrapping_sub(2);
+    cpu.memptr = cpu.prog_counter.wrapping_add(1);
 }
 
+/// Copy `(HL)` to `(DE)`, step both pointers by `delta` and decrement `BC`.
+///
+/// `S`, `Z` and `C` are unaffected. `PV` is set if `BC` is still non-zero, and the
+/// undocumented flags come from `A + (HL)`.
 #[inline]
-pub fn exchange_ldd(cpu: &mut Z80, mem: &mut [u8]) {
-    let src = cpu.hl as usize;
-    let dest = cpu.de as usize;
-    mem[dest] = mem[src];
-    cpu.de -= 1;
-    cpu.hl -= 1;
-    cpu.bc -= 1;
+fn block_load(cpu: &mut Z80, delta: i16, bus: &mut impl Bus) {
+    let val = bus.read(cpu.hl);
+    bus.write(cpu.de, val);
+    cpu.de = cpu.de.wrapping_add_signed(delta);
+    cpu.hl = cpu.hl.wrapping_add_signed(delta);
+    cpu.bc = cpu.bc.wrapping_sub(1);
 
     cpu.set_flag(Flag::H, false);
     cpu.set_flag(Flag::PV, cpu.bc != 0);
     cpu.set_flag(Flag::N, false);
+    block_xy_flags(cpu, (cpu.reg(Register::A) as u8).wrapping_add(val));
 }
 
+/// Compare `A` with `(HL)`, step `HL` and MEMPTR by `delta` and decrement `BC`.
+///
+/// `C` is unaffected. `PV` is set if `BC` is still non-zero, and the undocumented
+/// flags come from `A - (HL) - H`.
 #[inline]
-pub fn exchange_lddr(cpu: &mut Z80, mem: &mut [u8]) {
-    let src = cpu.hl as usize;
-    let dest = cpu.de as usize;
-    mem[dest] = mem[src];
-    cpu.de -= 1;
-    cpu.hl -= 1;
-    cpu.bc -= 1;
+fn block_compare(cpu: &mut Z80, delta: i16, bus: &mut impl Bus) {
+    let val = bus.read(cpu.hl);
+    let (diff, half) = (cpu.reg(Register::A) as u8).sub_borrow(val);
+    cpu.hl = cpu.hl.wrapping_add_signed(delta);
+    cpu.bc = cpu.bc.wrapping_sub(1);
+    cpu.memptr = cpu.memptr.wrapping_add_signed(delta);
 
-    cpu.set_flag(Flag::H, false);
+    cpu.set_flag(Flag::S, diff & 0x80 != 0);
+    cpu.set_flag(Flag::Z, diff == 0);
+    cpu.set_flag(Flag::H, half);
     cpu.set_flag(Flag::PV, cpu.bc != 0);
-    cpu.set_flag(Flag::N, false);
+    cpu.set_flag(Flag::N, true);
+    block_xy_flags(cpu, diff.wrapping_sub(half as u8));
+}
 
+#[inline]
+pub fn exchange_ldi(cpu: &mut Z80, bus: &mut impl Bus) {
+    block_load(cpu, 1, bus);
+}
+
+#[inline]
+pub fn exchange_ldir(cpu: &mut Z80, bus: &mut impl Bus) {
+    block_load(cpu, 1, bus);
     if cpu.bc != 0 {
-        cpu.prog_counter -= 2;
+        block_repeat(cpu);
     }
 }
 
 #[inline]
-pub fn exchange_cpi(cpu: &mut Z80, mem: &[u8]) {
-    let addr = cpu.reg(Register::HL) as usize;
-    let val = mem[addr] as i32;
-    let cmp = (cpu.reg(Register::A) as i32) - val;
-
-    cpu.hl += 1;
-    cpu.bc -= 1;
-
-    cpu.set_flag(Flag::S, cmp < 0);
-    cpu.set_flag(Flag::Z, cmp == 0);
-    cpu.set_flag(Flag::PV, cpu.bc != 0);
-    cpu.set_flag(Flag::N, true);
+pub fn exchange_ldd(cpu: &mut Z80, bus: &mut impl Bus) {
+    block_load(cpu, -1, bus);
 }
 
 #[inline]
-pub fn exchange_cpir(cpu: &mut Z80, mem: &[u8]) {
-    let addr = cpu.reg(Register::HL) as usize;
-    let val = mem[addr] as i32;
-    let cmp = (cpu.reg(Register::A) as i32) - val;
-    cpu.set_flag(Flag::N, cmp < 0);
-    cpu.set_flag(Flag::Z, cmp == 0);
-
-    cpu.hl += 1;
-    cpu.bc -= 1;
-    if cpu.bc != 0 || cmp != 0 {
-        cpu.prog_counter -= 2;
+pub fn exchange_lddr(cpu: &mut Z80, bus: &mut impl Bus) {
+    block_load(cpu, -1, bus);
+    if cpu.bc != 0 {
+        block_repeat(cpu);
     }
 }
 
 #[inline]
-pub fn exchange_cpd(cpu: &mut Z80, mem: &[u8]) {
-    let addr = cpu.reg(Register::HL) as usize;
-    let val = mem[addr] as i32;
-    let cmp = (cpu.reg(Register::A) as i32) - val;
+pub fn exchange_cpi(cpu: &mut Z80, bus: &mut impl Bus) {
+    block_compare(cpu, 1, bus);
+}
 
-    cpu.hl -= 1;
-    cpu.bc -= 1;
+#[inline]
+pub fn exchange_cpir(cpu: &mut Z80, bus: &mut impl Bus) {
+    block_compare(cpu, 1, bus);
+    if cpu.bc != 0 && !cpu.flag(Flag::Z) {
+        block_repeat(cpu);
+    }
+}
 
-    cpu.set_flag(Flag::S, cmp < 0);
-    cpu.set_flag(Flag::Z, cmp == 0);
-    cpu.set_flag(Flag::PV, cpu.bc != 0);
-    cpu.set_flag(Flag::N, true);
+#[inline]
+pub fn exchange_cpd(cpu: &mut Z80, bus: &mut impl Bus) {
+    block_compare(cpu, -1, bus);
 }
 
 #[inline]
-pub fn exchange_cpdr(cpu: &mut Z80, mem: &[u8]) {
-    let addr = cpu.reg(Register::HL) as usize;
-    let val = mem[addr] as i32;
-    let cmp = (cpu.reg(Register::A) as i32) - val;
+pub fn exchange_cpdr(cpu: &mut Z80, bus: &mut impl Bus) {
+    block_compare(cpu, -1, bus);
+    if cpu.bc != 0 && !cpu.flag(Flag::Z) {
+        block_repeat(cpu);
+    }
+}
 
-    cpu.hl -= 1;
-    cpu.bc -= 1;
+#[cfg(test)]
+mod exchange_tests {
+    use super::*;
+    use crate::{bus::Ram, Instruction};
+    use proptest::prelude::*;
+    use rstest::*;
+    use serde_json::Value;
 
-    cpu.set_flag(Flag::S, cmp < 0);
-    cpu.set_flag(Flag::Z, cmp == 0);
-    cpu.set_flag(Flag::PV, cpu.bc != 0);
-    cpu.set_flag(Flag::N, true);
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[fixture]
+    fn mem() -> Ram {
+        Ram::new()
+    }
+
+    #[rstest]
+    fn test_exchange_sp_hl_wraps(mut z80: Z80, mut mem: Ram) {
+        z80.stack_ptr = 0xffff;
+        mem[0xffff] = 0x34;
+        mem[0x0000] = 0x12;
+        exchange_sp_hl(&mut z80, &mut mem);
+        assert_eq!(0x1234, z80.hl);
+        assert_eq!(0x00, mem[0xffff]);
+        assert_eq!(0x00, mem[0x0000]);
+    }
+
+    #[rstest]
+    #[case::hl(Register::HL, Instruction::EX_SP_HL)]
+    #[case::ix(Register::IX, Instruction::EX_SP_IX)]
+    #[case::iy(Register::IY, Instruction::EX_SP_IY)]
+    fn test_exchange_sp(
+        mut z80: Z80,
+        mut mem: Ram,
+        #[case] reg: Register,
+        #[case] inst: Instruction,
+    ) {
+        z80.stack_ptr = 0x8000;
+        z80.set_reg(reg, 0x1234);
+        mem[0x8000] = 0x78;
+        mem[0x8001] = 0x56;
+        z80.execute(inst, &mut mem);
+        assert_eq!(0x5678, z80.reg(reg));
+        assert_eq!(0x34, mem[0x8000]);
+        assert_eq!(0x12, mem[0x8001]);
+        assert_eq!(0x8000, z80.stack_ptr);
+        assert_eq!(0x5678, z80.memptr);
+    }
+
+    #[rstest]
+    #[case::hl(Register::HL)]
+    #[case::ix(Register::IX)]
+    #[case::iy(Register::IY)]
+    fn test_exchange_sp_twice(mut z80: Z80, mut mem: Ram, #[case] reg: Register) {
+        z80.stack_ptr = 0xffff;
+        z80.set_reg(reg, 0xabcd);
+        mem[0xffff] = 0x34;
+        mem[0x0000] = 0x12;
+        let exchange = match reg {
+            Register::HL => exchange_sp_hl,
+            Register::IX => exchange_sp_ix,
+            _ => exchange_sp_iy,
+        };
+        exchange(&mut z80, &mut mem);
+        assert_eq!(0x1234, z80.reg(reg));
+        assert_eq!(0xcd, mem[0xffff]);
+        assert_eq!(0xab, mem[0x0000]);
+        exchange(&mut z80, &mut mem);
+        assert_eq!(0xabcd, z80.reg(reg));
+        assert_eq!(0x34, mem[0xffff]);
+        assert_eq!(0x12, mem[0x0000]);
+    }
+
+    #[rstest]
+    fn test_ldi_wraps(mut z80: Z80, mut mem: Ram) {
+        z80.hl = 0xffff;
+        z80.de = 0xffff;
+        z80.bc = 0x0000;
+        mem[0xffff] = 0x55;
+        exchange_ldi(&mut z80, &mut mem);
+        assert_eq!(0x0000, z80.hl);
+        assert_eq!(0x0000, z80.de);
+        assert_eq!(0xffff, z80.bc);
+        assert!(z80.flag(Flag::PV));
+    }
+
+    #[rstest]
+    fn test_ldi_last(mut z80: Z80, mut mem: Ram) {
+        z80.bc = 0x0001;
+        exchange_ldi(&mut z80, &mut mem);
+        assert_eq!(0x0000, z80.bc);
+        assert!(!z80.flag(Flag::PV));
+    }
+
+    #[rstest]
+    fn test_lddr_wraps(mut z80: Z80, mut mem: Ram) {
+        z80.prog_counter = 0x0001;
+        z80.hl = 0x0000;
+        z80.de = 0x0000;
+        z80.bc = 0x0002;
+        mem[0x0000] = 0x77;
+        exchange_lddr(&mut z80, &mut mem);
+        assert_eq!(0xffff, z80.hl);
+        assert_eq!(0xffff, z80.de);
+        assert_eq!(0x0001, z80.bc);
+        assert_eq!(0xffff, z80.prog_counter);
+    }
+
+    #[rstest]
+    #[case::ldi_y(Instruction::LDI, 0x11, 0x22, 0xff, 0x0002, 0xe5)]
+    #[case::ldi_x_last(Instruction::LDI, 0x05, 0x03, 0x00, 0x0001, 0x08)]
+    #[case::ldd_y(Instruction::LDD, 0x11, 0x22, 0xff, 0x0002, 0xe5)]
+    #[case::ldd_x_last(Instruction::LDD, 0x05, 0x03, 0x00, 0x0001, 0x08)]
+    #[case::ldir_last(Instruction::LDIR, 0x00, 0x00, 0x3a, 0x0001, 0x00)]
+    #[case::lddr_repeat(Instruction::LDDR, 0xf0, 0x12, 0xc1, 0x0000, 0xe5)]
+    fn test_block_load_flags(
+        mut z80: Z80,
+        #[case] inst: Instruction,
+        #[case] a: u8,
+        #[case] val: u8,
+        #[case] f: u8,
+        #[case] bc: u16,
+        #[case] expected: u8,
+    ) {
+        let mut mem = Ram::new();
+        z80.set_reg(Register::A, a as u16);
+        z80.set_reg(Register::F, f as u16);
+        z80.bc = bc;
+        z80.hl = 0x4000;
+        z80.de = 0x5000;
+        mem[0x4000] = val;
+        z80.execute(inst, &mut mem);
+        assert_eq!(val, mem[0x5000]);
+        assert_eq!(bc.wrapping_sub(1), z80.bc);
+        assert_eq!(expected, z80.reg(Register::F) as u8);
+    }
+
+    #[rstest]
+    #[case::cpi_half(Instruction::CPI, 0x10, 0x01, 0x01, 0x0002, 0x3f)]
+    #[case::cpi_match_last(Instruction::CPI, 0x42, 0x42, 0x00, 0x0001, 0x42)]
+    #[case::cpi_negative_last(Instruction::CPI, 0x00, 0x01, 0x00, 0x0001, 0xba)]
+    #[case::cpi_no_half(Instruction::CPI, 0x3a, 0x30, 0xff, 0x0001, 0x2b)]
+    #[case::cpd_half(Instruction::CPD, 0x80, 0x01, 0x00, 0x0002, 0x3e)]
+    #[case::cpd_match(Instruction::CPD, 0x42, 0x42, 0x01, 0x0002, 0x47)]
+    fn test_block_compare_flags(
+        mut z80: Z80,
+        #[case] inst: Instruction,
+        #[case] a: u8,
+        #[case] val: u8,
+        #[case] f: u8,
+        #[case] bc: u16,
+        #[case] expected: u8,
+    ) {
+        let mut mem = Ram::new();
+        z80.set_reg(Register::A, a as u16);
+        z80.set_reg(Register::F, f as u16);
+        z80.bc = bc;
+        z80.hl = 0x4000;
+        mem[0x4000] = val;
+        z80.execute(inst, &mut mem);
+        assert_eq!(a as u16, z80.reg(Register::A));
+        assert_eq!(bc.wrapping_sub(1), z80.bc);
+        assert_eq!(expected, z80.reg(Register::F) as u8);
+    }
+
+    #[rstest]
+    #[case::cpir_last(Instruction::CPIR, 0x0001, 0x01, false, 0x1001)]
+    #[case::cpir_match(Instruction::CPIR, 0x0002, 0x42, false, 0x1001)]
+    #[case::cpir_no_match(Instruction::CPIR, 0x0002, 0x01, true, 0x8001)]
+    #[case::cpir_bc_zero(Instruction::CPIR, 0x0000, 0x01, true, 0x8001)]
+    #[case::cpdr_last(Instruction::CPDR, 0x0001, 0x01, false, 0x0fff)]
+    #[case::cpdr_match(Instruction::CPDR, 0x0002, 0x42, false, 0x0fff)]
+    #[case::cpdr_no_match(Instruction::CPDR, 0x0002, 0x01, true, 0x8001)]
+    fn test_block_compare_repeat(
+        mut z80: Z80,
+        mut mem: Ram,
+        #[case] inst: Instruction,
+        #[case] bc: u16,
+        #[case] val: u8,
+        #[case] repeats: bool,
+        #[case] memptr: u16,
+    ) {
+        z80.prog_counter = 0x8002;
+        z80.memptr = 0x1000;
+        z80.set_reg(Register::A, 0x42);
+        z80.bc = bc;
+        z80.hl = 0x4000;
+        mem[0x4000] = val;
+        z80.execute(inst, &mut mem);
+        let pc = if repeats { 0x8000 } else { 0x8002 };
+        assert_eq!(pc, z80.prog_counter);
+        assert_eq!(memptr, z80.memptr);
+        assert_eq!(repeats, z80.flag(Flag::PV) && !z80.flag(Flag::Z));
+    }
+
+    #[rstest]
+    #[case::repeat(Instruction::LDIR, 0x0002, 0x8000, 0x8001)]
+    #[case::last(Instruction::LDIR, 0x0001, 0x8002, 0x1000)]
+    #[case::lddr_repeat(Instruction::LDDR, 0x0002, 0x8000, 0x8001)]
+    #[case::lddr_last(Instruction::LDDR, 0x0001, 0x8002, 0x1000)]
+    fn test_block_load_repeat(
+        mut z80: Z80,
+        mut mem: Ram,
+        #[case] inst: Instruction,
+        #[case] bc: u16,
+        #[case] pc: u16,
+        #[case] memptr: u16,
+    ) {
+        z80.prog_counter = 0x8002;
+        z80.memptr = 0x1000;
+        z80.bc = bc;
+        z80.execute(inst, &mut mem);
+        assert_eq!(pc, z80.prog_counter);
+        assert_eq!(memptr, z80.memptr);
+    }
+
+    #[rstest]
+    fn test_cpir_finds_byte(mut z80: Z80, mut mem: Ram) {
+        mem[0x0000..0x0002].copy_from_slice(&[0xed, 0xb1]);
+        mem[0x4000..0x4004].copy_from_slice(&[0x01, 0x02, 0x42, 0x03]);
+        z80.set_reg(Register::A, 0x42);
+        z80.bc = 0x0004;
+        z80.hl = 0x4000;
+        z80.run_until(&mut mem, |cpu| cpu.prog_counter != 0x0000);
+        assert_eq!(0x0002, z80.prog_counter);
+        assert_eq!(0x4003, z80.hl);
+        assert_eq!(0x0001, z80.bc);
+        assert!(z80.flag(Flag::Z));
+        assert!(z80.flag(Flag::PV));
+    }
+
+    #[rstest]
+    fn test_cpd_wraps(mut z80: Z80, mut mem: Ram) {
+        z80.hl = 0x0000;
+        z80.bc = 0x0000;
+        exchange_cpd(&mut z80, &mut mem);
+        assert_eq!(0xffff, z80.hl);
+        assert_eq!(0xffff, z80.bc);
+    }
+
+    /// Flags after `LDI` or `LDD`, as given in section 4.2 of Sean Young's "The
+    /// Undocumented Z80 Documented" (version 0.91).
+    fn documented_load_flags(f: u8, a: u8, val: u8, bc: u16) -> u8 {
+        let n = a.wrapping_add(val);
+        let pv = if bc != 0 { 0x04 } else { 0 };
+        (f & 0xc1) | (n & 0x08) | ((n & 0x02) << 4) | pv
+    }
+
+    /// Flags after `CPI` or `CPD`, as given in section 4.2 of Sean Young's "The
+    /// Undocumented Z80 Documented" (version 0.91).
+    fn documented_compare_flags(f: u8, a: u8, val: u8, bc: u16) -> u8 {
+        let res = a.wrapping_sub(val);
+        let h = (a & 0x0f) < (val & 0x0f);
+        let n = res.wrapping_sub(h as u8);
+        let s = res & 0x80;
+        let z = if res == 0 { 0x40 } else { 0 };
+        let pv = if bc != 0 { 0x04 } else { 0 };
+        s | z | (n & 0x08) | ((n & 0x02) << 4) | ((h as u8) << 4) | pv | 0x02 | (f & 0x01)
+    }
+
+    proptest! {
+        #[test]
+        fn test_block_flags_documented(
+            a in any::<u8>(),
+            val in any::<u8>(),
+            f in any::<u8>(),
+            bc in any::<u16>(),
+            inst in prop::sample::select(vec![
+                Instruction::LDI,
+                Instruction::LDD,
+                Instruction::CPI,
+                Instruction::CPD,
+            ]),
+        ) {
+            let mut z80 = z80();
+            let mut mem = Ram::new();
+            z80.set_reg(Register::A, a as u16);
+            z80.set_reg(Register::F, f as u16);
+            z80.bc = bc;
+            z80.hl = 0x4000;
+            z80.de = 0x5000;
+            mem[0x4000] = val;
+            z80.execute(inst, &mut mem);
+            let bc = bc.wrapping_sub(1);
+            let expected = match inst {
+                Instruction::LDI | Instruction::LDD => documented_load_flags(f, a, val, bc),
+                _ => documented_compare_flags(f, a, val, bc),
+            };
+            prop_assert_eq!(expected, z80.reg(Register::F) as u8);
+        }
+    }
+
+    /// Builds the CPU and memory described by a SingleStepTests state.
+    fn singlestep_state(state: &Value) -> (Z80, Vec<(u16, u8)>) {
+        let get = |name: &str| state[name].as_u64().unwrap() as u16;
+        let pair = |hi: &str, lo: &str| (get(hi) << 8) | get(lo);
+        let z80 = Z80 {
+            af: pair("a", "f"),
+            bc: pair("b", "c"),
+            de: pair("d", "e"),
+            hl: pair("h", "l"),
+            af1: get("af_"),
+            bc1: get("bc_"),
+            de1: get("de_"),
+            hl1: get("hl_"),
+            index_x: get("ix"),
+            index_y: get("iy"),
+            stack_ptr: get("sp"),
+            prog_counter: get("pc"),
+            interrupt: get("i") as u8,
+            refresh: get("r") as u8,
+            memptr: get("wz"),
+            iff1: get("iff1") != 0,
+            iff2: get("iff2") != 0,
+            ..Default::default()
+        };
+        let ram = state["ram"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|cell| {
+                (
+                    cell[0].as_u64().unwrap() as u16,
+                    cell[1].as_u64().unwrap() as u8,
+                )
+            })
+            .collect();
+        (z80, ram)
+    }
 
-    if cpu.bc != 0 || cmp != 0 {
-        cpu.prog_counter -= 2;
+    /// Runs the published per-instruction vectors from SingleStepTests
+    /// (<https://github.com/SingleStepTests/z80>). Set `SINGLESTEP_Z80` to the `v1`
+    /// directory of a checkout and run with `--ignored`.
+    #[rstest]
+    #[case::ldi("ed a0")]
+    #[case::cpi("ed a1")]
+    #[case::ldd("ed a8")]
+    #[case::cpd("ed a9")]
+    #[case::ldir("ed b0")]
+    #[case::cpir("ed b1")]
+    #[case::lddr("ed b8")]
+    #[case::cpdr("ed b9")]
+    #[ignore = "needs the SingleStepTests z80 vectors in $SINGLESTEP_Z80"]
+    fn test_singlestep_vectors(#[case] file: &str) {
+        let dir = std::env::var("SINGLESTEP_Z80").expect("SINGLESTEP_Z80 is not set");
+        let json = std::fs::read_to_string(format!("{}/{}.json", dir, file)).unwrap();
+        let tests: Vec<Value> = serde_json::from_str(&json).unwrap();
+        for test in &tests {
+            let name = test["name"].as_str().unwrap();
+            let (mut z80, ram) = singlestep_state(&test["initial"]);
+            let mut mem = Ram::new();
+            for (addr, val) in ram {
+                mem[addr as usize] = val;
+            }
+            z80.step(&mut mem).unwrap();
+            let (expected, ram) = singlestep_state(&test["final"]);
+            let registers = |cpu: &Z80| {
+                [
+                    cpu.af,
+                    cpu.bc,
+                    cpu.de,
+                    cpu.hl,
+                    cpu.stack_ptr,
+                    cpu.prog_counter,
+                    cpu.memptr,
+                    cpu.refresh as u16,
+                ]
+            };
+            assert_eq!(registers(&expected), registers(&z80), "{}", name);
+            for (addr, val) in ram {
+                assert_eq!(val, mem[addr as usize], "{} at {:04X}", name, addr);
+            }
+        }
     }
 }
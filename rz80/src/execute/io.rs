@@ -0,0 +1,247 @@
+//! Functions for executing Input and Output instructions.
+use super::{parity, xy_flags};
+use crate::{bus::Bus, hi_lo::HiLo, Flag, Register, Z80};
+
+/// Set the flags affected by `IN r, (C)`.
+#[inline]
+fn in_flags(cpu: &mut Z80, val: u8) {
+    cpu.set_flag(Flag::S, (val as i8) < 0);
+    cpu.set_flag(Flag::Z, val == 0);
+    cpu.set_flag(Flag::H, false);
+    cpu.set_flag(Flag::PV, parity(val));
+    cpu.set_flag(Flag::N, false);
+    xy_flags(cpu, val);
+}
+
+/// Set the flags affected by the block I/O instructions.
+///
+/// `k` is the transferred byte plus either the adjusted `C` (for input) or the
+/// updated `L` (for output).
+#[inline]
+fn block_flags(cpu: &mut Z80, val: u8, k: u16) {
+    let b = cpu.bc.hi();
+    cpu.set_flag(Flag::S, (b as i8) < 0);
+    cpu.set_flag(Flag::Z, b == 0);
+    cpu.set_flag(Flag::H, k > 0xff);
+    cpu.set_flag(Flag::PV, parity((k as u8 & 0x07) ^ b));
+    cpu.set_flag(Flag::N, val & 0x80 != 0);
+    cpu.set_flag(Flag::C, k > 0xff);
+    xy_flags(cpu, b);
+}
+
+#[inline]
+pub fn in_a_n(cpu: &mut Z80, n: u8, bus: &mut impl Bus) {
+    let port = (cpu.af & 0xff00) | n as u16;
+    cpu.memptr = port.wrapping_add(1);
+    cpu.set_reg(Register::A, bus.port_in(port) as u16);
+}
+
+#[inline]
+pub fn in_r_c(cpu: &mut Z80, r: Register, bus: &mut impl Bus) {
+    cpu.memptr = cpu.bc.wrapping_add(1);
+    let val = bus.port_in(cpu.bc);
+    cpu.set_reg(r, val as u16);
+    in_flags(cpu, val);
+}
+
+#[inline]
+pub fn in_c(cpu: &mut Z80, bus: &mut impl Bus) {
+    cpu.memptr = cpu.bc.wrapping_add(1);
+    let val = bus.port_in(cpu.bc);
+    in_flags(cpu, val);
+}
+
+#[inline]
+pub fn out_n_a(cpu: &mut Z80, n: u8, bus: &mut impl Bus) {
+    let port = (cpu.af & 0xff00) | n as u16;
+    cpu.memptr = (cpu.af & 0xff00) | n.wrapping_add(1) as u16;
+    bus.port_out(port, cpu.af.hi());
+}
+
+#[inline]
+pub fn out_c_r(cpu: &mut Z80, r: Register, bus: &mut impl Bus) {
+    cpu.memptr = cpu.bc.wrapping_add(1);
+    bus.port_out(cpu.bc, cpu.reg(r) as u8);
+}
+
+#[inline]
+pub fn out_c_0(cpu: &mut Z80, bus: &mut impl Bus) {
+    cpu.memptr = cpu.bc.wrapping_add(1);
+    bus.port_out(cpu.bc, 0);
+}
+
+#[inline]
+pub fn ini(cpu: &mut Z80, bus: &mut impl Bus) {
+    cpu.memptr = cpu.bc.wrapping_add(1);
+    let val = bus.port_in(cpu.bc);
+    bus.write(cpu.hl, val);
+    cpu.bc.set_hi(cpu.bc.hi().wrapping_sub(1));
+    cpu.hl = cpu.hl.wrapping_add(1);
+    let k = val as u16 + cpu.bc.lo().wrapping_add(1) as u16;
+    block_flags(cpu, val, k);
+}
+
+#[inline]
+pub fn inir(cpu: &mut Z80, bus: &mut impl Bus) {
+    ini(cpu, bus);
+    if cpu.bc.hi() != 0 {
+        cpu.prog_counter = cpu.prog_counter.wrapping_sub(2);
+    }
+}
+
+#[inline]
+pub fn ind(cpu: &mut Z80, bus: &mut impl Bus) {
+    cpu.memptr = cpu.bc.wrapping_sub(1);
+    let val = bus.port_in(cpu.bc);
+    bus.write(cpu.hl, val);
+    cpu.bc.set_hi(cpu.bc.hi().wrapping_sub(1));
+    cpu.hl = cpu.hl.wrapping_sub(1);
+    let k = val as u16 + cpu.bc.lo().wrapping_sub(1) as u16;
+    block_flags(cpu, val, k);
+}
+
+#[inline]
+pub fn indr(cpu: &mut Z80, bus: &mut impl Bus) {
+    ind(cpu, bus);
+    if cpu.bc.hi() != 0 {
+        cpu.prog_counter = cpu.prog_counter.wrapping_sub(2);
+    }
+}
+
+#[inline]
+pub fn outi(cpu: &mut Z80, bus: &mut impl Bus) {
+    let val = bus.read(cpu.hl);
+    cpu.bc.set_hi(cpu.bc.hi().wrapping_sub(1));
+    bus.port_out(cpu.bc, val);
+    cpu.memptr = cpu.bc.wrapping_add(1);
+    cpu.hl = cpu.hl.wrapping_add(1);
+    let k = val as u16 + cpu.hl.lo() as u16;
+    block_flags(cpu, val, k);
+}
+
+#[inline]
+pub fn otir(cpu: &mut Z80, bus: &mut impl Bus) {
+    outi(cpu, bus);
+    if cpu.bc.hi() != 0 {
+        cpu.prog_counter = cpu.prog_counter.wrapping_sub(2);
+    }
+}
+
+#[inline]
+pub fn outd(cpu: &mut Z80, bus: &mut impl Bus) {
+    let val = bus.read(cpu.hl);
+    cpu.bc.set_hi(cpu.bc.hi().wrapping_sub(1));
+    bus.port_out(cpu.bc, val);
+    cpu.memptr = cpu.bc.wrapping_sub(1);
+    cpu.hl = cpu.hl.wrapping_sub(1);
+    let k = val as u16 + cpu.hl.lo() as u16;
+    block_flags(cpu, val, k);
+}
+
+#[inline]
+pub fn otdr(cpu: &mut Z80, bus: &mut impl Bus) {
+    outd(cpu, bus);
+    if cpu.bc.hi() != 0 {
+        cpu.prog_counter = cpu.prog_counter.wrapping_sub(2);
+    }
+}
+
+#[cfg(test)]
+mod io_tests {
+    use super::*;
+    use crate::{bus::Ram, io::IoBus};
+    use rstest::*;
+
+    /// Records every port access and answers reads with the low byte of the port.
+    #[derive(Default)]
+    struct Ports {
+        reads: Vec<u16>,
+        writes: Vec<(u16, u8)>,
+    }
+
+    impl IoBus for Ports {
+        fn port_in(&mut self, port: u16) -> u8 {
+            self.reads.push(port);
+            port as u8
+        }
+
+        fn port_out(&mut self, port: u16, val: u8) {
+            self.writes.push((port, val));
+        }
+    }
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[fixture]
+    fn bus() -> Ram<Ports> {
+        Ram::with_io(Default::default())
+    }
+
+    #[rstest]
+    fn test_in_a_n(mut z80: Z80, mut bus: Ram<Ports>) {
+        z80.set_reg(Register::A, 0x7f);
+        in_a_n(&mut z80, 0xfe, &mut bus);
+        assert_eq!(vec![0x7ffe], bus.io.reads);
+        assert_eq!(0xfe, z80.reg(Register::A));
+        assert_eq!(0x7fff, z80.memptr);
+    }
+
+    #[rstest]
+    fn test_in_r_c(mut z80: Z80, mut bus: Ram<Ports>) {
+        z80.bc = 0x1233;
+        in_r_c(&mut z80, Register::D, &mut bus);
+        assert_eq!(vec![0x1233], bus.io.reads);
+        assert_eq!(0x33, z80.reg(Register::D));
+        assert!(z80.flag(Flag::PV));
+        assert_eq!(0x20, z80.reg(Register::F) as u8 & 0x28);
+        assert_eq!(0x1234, z80.memptr);
+    }
+
+    #[rstest]
+    fn test_out(mut z80: Z80, mut bus: Ram<Ports>) {
+        z80.set_reg(Register::A, 0x12);
+        z80.bc = 0xabcd;
+        z80.set_reg(Register::E, 0x99);
+        out_n_a(&mut z80, 0xfe, &mut bus);
+        out_c_r(&mut z80, Register::E, &mut bus);
+        out_c_0(&mut z80, &mut bus);
+        assert_eq!(
+            vec![(0x12fe, 0x12), (0xabcd, 0x99), (0xabcd, 0x00)],
+            bus.io.writes
+        );
+    }
+
+    #[rstest]
+    fn test_inir(mut z80: Z80, mut bus: Ram<Ports>) {
+        z80.bc = 0x0210;
+        z80.hl = 0x0004;
+        z80.prog_counter = 0x0102;
+        inir(&mut z80, &mut bus);
+        assert_eq!(0x0100, z80.prog_counter);
+        inir(&mut z80, &mut bus);
+        assert_eq!(0x0100, z80.prog_counter);
+        assert_eq!(vec![0x0210, 0x0110], bus.io.reads);
+        assert_eq!(0x10, bus[4]);
+        assert_eq!(0x10, bus[5]);
+        assert_eq!(0x0010, z80.bc);
+        assert_eq!(0x0006, z80.hl);
+    }
+
+    #[rstest]
+    fn test_otdr(mut z80: Z80, mut bus: Ram<Ports>) {
+        bus[..3].copy_from_slice(&[0x11, 0x22, 0x33]);
+        z80.bc = 0x0210;
+        z80.hl = 0x0002;
+        z80.prog_counter = 0x0102;
+        otdr(&mut z80, &mut bus);
+        assert_eq!(0x0100, z80.prog_counter);
+        z80.prog_counter = 0x0102;
+        otdr(&mut z80, &mut bus);
+        assert_eq!(0x0102, z80.prog_counter);
+        assert_eq!(vec![(0x0110, 0x33), (0x0010, 0x22)], bus.io.writes);
+        assert_eq!(0x0000, z80.hl);
+    }
+}
@@ -1,45 +1,32 @@
 //! Functions for executing Jump instructions.
-use crate::{Condition, Flag, Register, Z80};
+use crate::{Condition, Register, Z80};
 
 #[inline]
 pub fn jump_nn(cpu: &mut Z80, nn: u16) {
+    cpu.memptr = nn;
     cpu.prog_counter = nn;
 }
 
+/// Jump to `nn` if `cc` holds. MEMPTR is set to `nn` whether or not the jump is taken.
 #[inline]
 pub fn jump_cc_nn(cpu: &mut Z80, cc: Condition, nn: u16) {
-    let flag = match cc {
-        Condition::Z => cpu.flag(Flag::Z),
-        Condition::NZ => !cpu.flag(Flag::Z),
-        Condition::C => cpu.flag(Flag::C),
-        Condition::NC => !cpu.flag(Flag::C),
-        Condition::PO => cpu.flag(Flag::PV),
-        Condition::PE => !cpu.flag(Flag::PV),
-        Condition::P => cpu.flag(Flag::S),
-        Condition::M => !cpu.flag(Flag::S),
-    };
-
-    if flag {
+    cpu.memptr = nn;
+    if cpu.condition(cc) {
         cpu.prog_counter = nn;
     }
 }
 
+/// Jump relative to the program counter, which already holds the address of the next
+/// instruction. The target wraps around the 64K address space.
 #[inline]
 pub fn jumpr_e(cpu: &mut Z80, e: i8) {
-    let new_pc = (cpu.prog_counter as i32) + (e as i32);
-    cpu.prog_counter = new_pc as u16;
-}
-
-#[inline]
-pub fn jr_flag_e(cpu: &mut Z80, flag: Flag, e: i8) {
-    if cpu.flag(flag) {
-        jumpr_e(cpu, e);
-    }
+    cpu.prog_counter = cpu.prog_counter.wrapping_add(e as u16);
+    cpu.memptr = cpu.prog_counter;
 }
 
 #[inline]
-pub fn jr_nflag_e(cpu: &mut Z80, flag: Flag, e: i8) {
-    if !cpu.flag(flag) {
+pub fn jr_cc_e(cpu: &mut Z80, cc: Condition, e: i8) {
+    if cpu.condition(cc) {
         jumpr_e(cpu, e);
     }
 }
@@ -61,9 +48,59 @@ pub fn jump_iy(cpu: &mut Z80) {
 
 #[inline]
 pub fn djnz_e(cpu: &mut Z80, e: i8) {
-    let b = cpu.reg(Register::B);
-    cpu.set_reg(Register::B, b - 1);
-    if b - 1 != 0 {
+    let b = (cpu.reg(Register::B) as u8).wrapping_sub(1);
+    cpu.set_reg(Register::B, b as u16);
+    if b != 0 {
         jumpr_e(cpu, e);
     }
 }
+
+#[cfg(test)]
+mod jump_tests {
+    use super::*;
+    use crate::bus::Ram;
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[rstest]
+    #[case::forward(0x1002, 0x10, 0x1012)]
+    #[case::backward(0x1002, -2, 0x1000)]
+    #[case::furthest_forward(0x1002, 127, 0x1081)]
+    #[case::furthest_backward(0x1002, -128, 0x0f82)]
+    #[case::wrap_forward(0xfff0, 0x20, 0x0010)]
+    #[case::wrap_backward(0x0001, -3, 0xfffe)]
+    fn test_jumpr_e(mut z80: Z80, #[case] pc: u16, #[case] e: i8, #[case] expected: u16) {
+        z80.prog_counter = pc;
+        jumpr_e(&mut z80, e);
+        assert_eq!(expected, z80.prog_counter);
+        assert_eq!(expected, z80.memptr);
+    }
+
+    #[rstest]
+    #[case::taken(0x02, 0x01, 0x0ffe)]
+    #[case::not_taken(0x01, 0x00, 0x1000)]
+    #[case::wraps_b(0x00, 0xff, 0x0ffe)]
+    fn test_djnz_e(mut z80: Z80, #[case] b: u8, #[case] expected_b: u8, #[case] pc: u16) {
+        z80.prog_counter = 0x1000;
+        z80.set_reg(Register::B, b as u16);
+        djnz_e(&mut z80, -2);
+        assert_eq!(expected_b as u16, z80.reg(Register::B));
+        assert_eq!(pc, z80.prog_counter);
+    }
+
+    #[rstest]
+    fn test_jr_across_top_of_memory(mut z80: Z80) {
+        let mut mem = Ram::new();
+        mem[0xfffe] = 0x18;
+        mem[0xffff] = 0x04;
+        z80.prog_counter = 0xfffe;
+        let (inst, width) = z80.fetch(&mut mem).unwrap();
+        z80.prog_counter = z80.prog_counter.wrapping_add(width as u16);
+        z80.execute(inst, &mut mem);
+        assert_eq!(0x0004, z80.prog_counter);
+    }
+}
@@ -0,0 +1,234 @@
+//! Functions for executing 16-bit Load instructions.
+use crate::{bus::Bus, hi_lo::HiLo, Register, Z80};
+
+/// Read a little-endian word from memory, wrapping around at the top of the address space.
+#[inline]
+pub fn read_word(bus: &mut impl Bus, addr: u16) -> u16 {
+    let lo = bus.read(addr) as u16;
+    let hi = bus.read(addr.wrapping_add(1)) as u16;
+    (hi << 8) | lo
+}
+
+/// Write a little-endian word to memory, wrapping around at the top of the address space.
+#[inline]
+pub fn write_word(bus: &mut impl Bus, addr: u16, val: u16) {
+    bus.write(addr, val.lo());
+    bus.write(addr.wrapping_add(1), val.hi());
+}
+
+/// Push a word onto the stack, decrementing the stack pointer by 2.
+#[inline]
+pub fn push(cpu: &mut Z80, val: u16, bus: &mut impl Bus) {
+    cpu.stack_ptr = cpu.stack_ptr.wrapping_sub(1);
+    bus.write(cpu.stack_ptr, val.hi());
+    cpu.stack_ptr = cpu.stack_ptr.wrapping_sub(1);
+    bus.write(cpu.stack_ptr, val.lo());
+}
+
+/// Pop a word off the stack, incrementing the stack pointer by 2.
+#[inline]
+pub fn pop(cpu: &mut Z80, bus: &mut impl Bus) -> u16 {
+    let val = read_word(bus, cpu.stack_ptr);
+    cpu.stack_ptr = cpu.stack_ptr.wrapping_add(2);
+    val
+}
+
+#[inline]
+pub fn load_dd_nn(cpu: &mut Z80, dd: Register, nn: u16) {
+    cpu.set_reg(dd, nn);
+}
+
+#[inline]
+pub fn load_ix_nn(cpu: &mut Z80, nn: u16) {
+    cpu.index_x = nn;
+}
+
+#[inline]
+pub fn load_iy_nn(cpu: &mut Z80, nn: u16) {
+    cpu.index_y = nn;
+}
+
+#[inline]
+pub fn load_dd_inn(cpu: &mut Z80, dd: Register, nn: u16, bus: &mut impl Bus) {
+    cpu.memptr = nn.wrapping_add(1);
+    cpu.set_reg(dd, read_word(bus, nn));
+}
+
+#[inline]
+pub fn load_ix_inn(cpu: &mut Z80, nn: u16, bus: &mut impl Bus) {
+    cpu.memptr = nn.wrapping_add(1);
+    cpu.index_x = read_word(bus, nn);
+}
+
+#[inline]
+pub fn load_iy_inn(cpu: &mut Z80, nn: u16, bus: &mut impl Bus) {
+    cpu.memptr = nn.wrapping_add(1);
+    cpu.index_y = read_word(bus, nn);
+}
+
+#[inline]
+pub fn load_nn_dd(cpu: &mut Z80, nn: u16, dd: Register, bus: &mut impl Bus) {
+    cpu.memptr = nn.wrapping_add(1);
+    write_word(bus, nn, cpu.reg(dd));
+}
+
+#[inline]
+pub fn load_nn_ix(cpu: &mut Z80, nn: u16, bus: &mut impl Bus) {
+    cpu.memptr = nn.wrapping_add(1);
+    write_word(bus, nn, cpu.index_x);
+}
+
+#[inline]
+pub fn load_nn_iy(cpu: &mut Z80, nn: u16, bus: &mut impl Bus) {
+    cpu.memptr = nn.wrapping_add(1);
+    write_word(bus, nn, cpu.index_y);
+}
+
+#[inline]
+pub fn load_sp_hl(cpu: &mut Z80) {
+    cpu.stack_ptr = cpu.hl;
+}
+
+#[inline]
+pub fn load_sp_ix(cpu: &mut Z80) {
+    cpu.stack_ptr = cpu.index_x;
+}
+
+#[inline]
+pub fn load_sp_iy(cpu: &mut Z80) {
+    cpu.stack_ptr = cpu.index_y;
+}
+
+#[inline]
+pub fn push_qq(cpu: &mut Z80, qq: Register, bus: &mut impl Bus) {
+    let val = cpu.reg(qq);
+    push(cpu, val, bus);
+}
+
+#[inline]
+pub fn push_ix(cpu: &mut Z80, bus: &mut impl Bus) {
+    push(cpu, cpu.index_x, bus);
+}
+
+#[inline]
+pub fn push_iy(cpu: &mut Z80, bus: &mut impl Bus) {
+    push(cpu, cpu.index_y, bus);
+}
+
+#[inline]
+pub fn pop_qq(cpu: &mut Z80, qq: Register, bus: &mut impl Bus) {
+    let val = pop(cpu, bus);
+    cpu.set_reg(qq, val);
+}
+
+#[inline]
+pub fn pop_ix(cpu: &mut Z80, bus: &mut impl Bus) {
+    cpu.index_x = pop(cpu, bus);
+}
+
+#[inline]
+pub fn pop_iy(cpu: &mut Z80, bus: &mut impl Bus) {
+    cpu.index_y = pop(cpu, bus);
+}
+
+#[cfg(test)]
+mod load16_tests {
+    use super::*;
+    use crate::bus::Ram;
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[fixture]
+    fn mem() -> Ram {
+        Ram::new()
+    }
+
+    #[rstest]
+    #[case::bc(Register::BC)]
+    #[case::de(Register::DE)]
+    #[case::hl(Register::HL)]
+    #[case::sp(Register::SP)]
+    fn test_load_dd_nn(mut z80: Z80, #[case] dd: Register) {
+        load_dd_nn(&mut z80, dd, 0x1234);
+        assert_eq!(0x1234, z80.reg(dd));
+    }
+
+    #[rstest]
+    fn test_load_dd_inn(mut z80: Z80, mut mem: Ram) {
+        mem[0x5000] = 0x34;
+        mem[0x5001] = 0x12;
+        load_dd_inn(&mut z80, Register::HL, 0x5000, &mut mem);
+        assert_eq!(0x1234, z80.hl);
+        load_ix_inn(&mut z80, 0x5000, &mut mem);
+        assert_eq!(0x1234, z80.index_x);
+        load_iy_inn(&mut z80, 0x5000, &mut mem);
+        assert_eq!(0x1234, z80.index_y);
+    }
+
+    #[rstest]
+    fn test_load_nn_dd(mut z80: Z80, mut mem: Ram) {
+        z80.de = 0xabcd;
+        load_nn_dd(&mut z80, 0x5000, Register::DE, &mut mem);
+        assert_eq!(0xcd, mem[0x5000]);
+        assert_eq!(0xab, mem[0x5001]);
+    }
+
+    #[rstest]
+    fn test_load_nn_ix_wraps(mut z80: Z80, mut mem: Ram) {
+        z80.index_x = 0xabcd;
+        load_nn_ix(&mut z80, 0xffff, &mut mem);
+        assert_eq!(0xcd, mem[0xffff]);
+        assert_eq!(0xab, mem[0x0000]);
+        assert_eq!(0x0000, z80.memptr);
+    }
+
+    #[rstest]
+    fn test_load_sp(mut z80: Z80) {
+        z80.hl = 0x1111;
+        z80.index_x = 0x2222;
+        z80.index_y = 0x3333;
+        load_sp_hl(&mut z80);
+        assert_eq!(0x1111, z80.stack_ptr);
+        load_sp_ix(&mut z80);
+        assert_eq!(0x2222, z80.stack_ptr);
+        load_sp_iy(&mut z80);
+        assert_eq!(0x3333, z80.stack_ptr);
+    }
+
+    #[rstest]
+    fn test_push_qq(mut z80: Z80, mut mem: Ram) {
+        z80.stack_ptr = 0x1007;
+        z80.af = 0x2233;
+        push_qq(&mut z80, Register::AF, &mut mem);
+        assert_eq!(0x1005, z80.stack_ptr);
+        assert_eq!(0x22, mem[0x1006]);
+        assert_eq!(0x33, mem[0x1005]);
+    }
+
+    #[rstest]
+    fn test_pop_qq(mut z80: Z80, mut mem: Ram) {
+        z80.stack_ptr = 0x1000;
+        mem[0x1000] = 0x55;
+        mem[0x1001] = 0x33;
+        pop_qq(&mut z80, Register::HL, &mut mem);
+        assert_eq!(0x1002, z80.stack_ptr);
+        assert_eq!(0x3355, z80.hl);
+    }
+
+    #[rstest]
+    fn test_push_pop_ix_iy(mut z80: Z80, mut mem: Ram) {
+        z80.stack_ptr = 0x0001;
+        z80.index_x = 0x1234;
+        push_ix(&mut z80, &mut mem);
+        assert_eq!(0xffff, z80.stack_ptr);
+        assert_eq!(0x12, mem[0x0000]);
+        assert_eq!(0x34, mem[0xffff]);
+        pop_iy(&mut z80, &mut mem);
+        assert_eq!(0x0001, z80.stack_ptr);
+        assert_eq!(0x1234, z80.index_y);
+    }
+}
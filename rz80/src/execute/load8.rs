@@ -1,5 +1,6 @@
 //! Functions for executing 8-bit Load instructions.
-use crate::{Z80, Register};
+use super::xy_flags;
+use crate::{bus::Bus, hi_lo::HiLo, Flag, Register, Z80};
 
 #[inline]
 pub fn load_r_r(cpu: &mut Z80, r: Register, r1: Register) {
@@ -13,107 +14,139 @@ pub fn load_r_n(cpu: &mut Z80, r: Register, n: u8) {
 }
 
 #[inline]
-pub fn load_r_hl(cpu: &mut Z80, r: Register, mem: &[u8]) {
+pub fn load_r_hl(cpu: &mut Z80, r: Register, bus: &mut impl Bus) {
     let addr = cpu.reg(Register::HL);
-    let nn = mem[addr as usize];
+    let nn = bus.read(addr);
     cpu.set_reg(r, nn as u16);
 }
 
 #[inline]
-pub fn load_r_ix(cpu: &mut Z80, r: Register, d: i8, mem: &[u8]) {
-    let addr = cpu.index_x as i32 + d as i32;
-    let nn = mem[addr as usize];
+pub fn load_r_ix(cpu: &mut Z80, r: Register, d: i8, bus: &mut impl Bus) {
+    let addr = cpu.index_x.wrapping_add(d as u16);
+    cpu.memptr = addr;
+    let nn = bus.read(addr);
     cpu.set_reg(r, nn as u16);
 }
 
 #[inline]
-pub fn load_r_iy(cpu: &mut Z80, r: Register, d: i8, mem: &[u8]) {
-    let addr = cpu.index_y as i32 + d as i32;
-    let nn = mem[addr as usize];
+pub fn load_r_iy(cpu: &mut Z80, r: Register, d: i8, bus: &mut impl Bus) {
+    let addr = cpu.index_y.wrapping_add(d as u16);
+    cpu.memptr = addr;
+    let nn = bus.read(addr);
     cpu.set_reg(r, nn as u16);
 }
 
 #[inline]
-pub fn load_hl_r(cpu: &mut Z80, r: Register, mem: &mut [u8]) {
+pub fn load_hl_r(cpu: &mut Z80, r: Register, bus: &mut impl Bus) {
     let addr = cpu.reg(Register::HL);
-    mem[addr as usize] = cpu.reg(r) as u8;
+    bus.write(addr, cpu.reg(r) as u8);
 }
 
 #[inline]
-pub fn load_ix_r(cpu: &mut Z80, d: i8, r: Register, mem: &mut [u8]) {
-    let addr = cpu.index_x as i32 + d as i32;
-    mem[addr as usize] = cpu.reg(r) as u8;
+pub fn load_ix_r(cpu: &mut Z80, d: i8, r: Register, bus: &mut impl Bus) {
+    let addr = cpu.index_x.wrapping_add(d as u16);
+    cpu.memptr = addr;
+    bus.write(addr, cpu.reg(r) as u8);
 }
 
 #[inline]
-pub fn load_iy_r(cpu: &mut Z80, d: i8, r: Register, mem: &mut [u8]) {
-    let addr = cpu.index_y as i32 + d as i32;
-    mem[addr as usize] = cpu.reg(r) as u8;
+pub fn load_iy_r(cpu: &mut Z80, d: i8, r: Register, bus: &mut impl Bus) {
+    let addr = cpu.index_y.wrapping_add(d as u16);
+    cpu.memptr = addr;
+    bus.write(addr, cpu.reg(r) as u8);
 }
 
 #[inline]
-pub fn load_hl_n(cpu: &mut Z80, n: u8, mem: &mut [u8]) {
+pub fn load_hl_n(cpu: &mut Z80, n: u8, bus: &mut impl Bus) {
     let addr = cpu.reg(Register::HL);
-    mem[addr as usize] = n;
+    bus.write(addr, n);
 }
 
 #[inline]
-pub fn load_ix_n(cpu: &mut Z80, d: i8, n: u8, mem: &mut [u8]) {
-    let addr = cpu.index_x as i32 + d as i32;
-    mem[addr as usize] = n;
+pub fn load_ix_n(cpu: &mut Z80, d: i8, n: u8, bus: &mut impl Bus) {
+    let addr = cpu.index_x.wrapping_add(d as u16);
+    cpu.memptr = addr;
+    bus.write(addr, n);
 }
 
 #[inline]
-pub fn load_iy_n(cpu: &mut Z80, d: i8, n: u8, mem: &mut [u8]) {
-    let addr = cpu.index_y as i32 + d as i32;
-    mem[addr as usize] = n;
+pub fn load_iy_n(cpu: &mut Z80, d: i8, n: u8, bus: &mut impl Bus) {
+    let addr = cpu.index_y.wrapping_add(d as u16);
+    cpu.memptr = addr;
+    bus.write(addr, n);
 }
 
 #[inline]
-pub fn load_a_bc(cpu: &mut Z80, mem: &[u8]) {
-    let addr = cpu.reg(Register::BC) as usize;
-    let n = mem[addr] as u16;
+pub fn load_a_bc(cpu: &mut Z80, bus: &mut impl Bus) {
+    let addr = cpu.reg(Register::BC);
+    cpu.memptr = cpu.bc.wrapping_add(1);
+    let n = bus.read(addr) as u16;
     cpu.set_reg(Register::A, n);
 }
 
 #[inline]
-pub fn load_a_de(cpu: &mut Z80, mem: &[u8]) {
-    let addr = cpu.reg(Register::DE) as usize;
-    let n = mem[addr] as u16;
+pub fn load_a_de(cpu: &mut Z80, bus: &mut impl Bus) {
+    let addr = cpu.reg(Register::DE);
+    cpu.memptr = cpu.de.wrapping_add(1);
+    let n = bus.read(addr) as u16;
     cpu.set_reg(Register::A, n);
 }
 
 #[inline]
-pub fn load_a_nn(cpu: &mut Z80, addr: u16, mem: &[u8]) {
-    let n = mem[addr as usize] as u16;
+pub fn load_a_nn(cpu: &mut Z80, addr: u16, bus: &mut impl Bus) {
+    cpu.memptr = addr.wrapping_add(1);
+    let n = bus.read(addr) as u16;
     cpu.set_reg(Register::A, n);
 }
 
 #[inline]
-pub fn load_bc_a(cpu: &mut Z80, mem: &mut [u8]) {
+pub fn load_bc_a(cpu: &mut Z80, bus: &mut impl Bus) {
     let addr = cpu.reg(Register::BC);
-    mem[addr as usize] = cpu.reg(Register::A) as u8;
+    bus.write(addr, cpu.reg(Register::A) as u8);
+    store_memptr(cpu, addr);
 }
 
 #[inline]
-pub fn load_de_a(cpu: &mut Z80, mem: &mut [u8]) {
+pub fn load_de_a(cpu: &mut Z80, bus: &mut impl Bus) {
     let addr = cpu.reg(Register::DE);
-    mem[addr as usize] = cpu.reg(Register::A) as u8;
+    bus.write(addr, cpu.reg(Register::A) as u8);
+    store_memptr(cpu, addr);
+}
+
+#[inline]
+pub fn load_nn_a(cpu: &mut Z80, nn: u16, bus: &mut impl Bus) {
+    bus.write(nn, cpu.reg(Register::A) as u8);
+    store_memptr(cpu, nn);
 }
 
+/// Set MEMPTR after storing `A` at `addr`: the low byte is that of `addr + 1`, and
+/// the high byte is `A`.
 #[inline]
-pub fn load_nn_a(cpu: &mut Z80, nn: u16, mem: &mut [u8]) {
-    mem[nn as usize] = cpu.reg(Register::A) as u8;
+fn store_memptr(cpu: &mut Z80, addr: u16) {
+    cpu.memptr = ((cpu.af.hi() as u16) << 8) | addr.wrapping_add(1).lo() as u16;
 }
 
 #[inline]
 pub fn load_a_i(cpu: &mut Z80) {
     cpu.set_reg(Register::A, cpu.interrupt as u16);
+    special_flags(cpu, cpu.interrupt);
 }
 
 #[inline]
 pub fn load_a_r(cpu: &mut Z80) {
     cpu.set_reg(Register::A, cpu.refresh as u16);
+    special_flags(cpu, cpu.refresh);
+}
+
+/// Set the flags affected by `LD A, I` and `LD A, R`, which copy `IFF2` into P/V.
+#[inline]
+fn special_flags(cpu: &mut Z80, val: u8) {
+    cpu.set_flag(Flag::S, (val as i8) < 0);
+    cpu.set_flag(Flag::Z, val == 0);
+    cpu.set_flag(Flag::H, false);
+    cpu.set_flag(Flag::PV, cpu.iff2);
+    cpu.set_flag(Flag::N, false);
+    xy_flags(cpu, val);
 }
 
 #[inline]
@@ -124,4 +157,57 @@ pub fn load_i_a(cpu: &mut Z80) {
 #[inline]
 pub fn load_r_a(cpu: &mut Z80) {
     cpu.refresh = cpu.reg(Register::A) as u8;
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod load8_tests {
+    use super::*;
+    use crate::bus::Ram;
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[fixture]
+    fn mem() -> Ram {
+        Ram::new()
+    }
+
+    #[rstest]
+    #[case::forward(0xfffe, 3, 0x0001)]
+    #[case::backward(0x0001, -2, 0xffff)]
+    fn test_indexed_wraps(
+        mut z80: Z80,
+        mut mem: Ram,
+        #[case] base: u16,
+        #[case] d: i8,
+        #[case] addr: usize,
+    ) {
+        z80.index_x = base;
+        z80.index_y = base;
+        mem[addr] = 0x42;
+        load_r_ix(&mut z80, Register::B, d, &mut mem);
+        assert_eq!(0x42, z80.reg(Register::B));
+        load_iy_n(&mut z80, d, 0x99, &mut mem);
+        assert_eq!(0x99, mem[addr]);
+        assert_eq!(addr as u16, z80.memptr);
+    }
+
+    #[rstest]
+    fn test_extended_wraps_memptr(mut z80: Z80, mut mem: Ram) {
+        mem[0xffff] = 0x12;
+        load_a_nn(&mut z80, 0xffff, &mut mem);
+        assert_eq!(0x12, z80.reg(Register::A));
+        assert_eq!(0x0000, z80.memptr);
+    }
+
+    #[rstest]
+    fn test_register_indirect(mut z80: Z80, mut mem: Ram) {
+        z80.bc = 0xffff;
+        z80.set_reg(Register::A, 0x56);
+        load_bc_a(&mut z80, &mut mem);
+        assert_eq!(0x56, mem[0xffff]);
+        assert_eq!(0x5600, z80.memptr);
+    }
+}
@@ -0,0 +1,279 @@
+//! Functions for executing Rotate and Shift instructions.
+use super::{parity, xy_flags};
+use crate::{bus::Bus, Flag, Register, Z80};
+
+/// Signature shared by the CB-prefixed rotate and shift operations.
+pub type ShiftOp = fn(&mut Z80, u8) -> u8;
+
+/// Set the flags shared by every CB-prefixed rotate and shift.
+#[inline]
+fn shift_flags(cpu: &mut Z80, val: u8, carry: bool) {
+    cpu.set_flag(Flag::S, (val as i8) < 0);
+    cpu.set_flag(Flag::Z, val == 0);
+    cpu.set_flag(Flag::H, false);
+    cpu.set_flag(Flag::PV, parity(val));
+    cpu.set_flag(Flag::N, false);
+    cpu.set_flag(Flag::C, carry);
+    xy_flags(cpu, val);
+}
+
+/// Set the flags affected by the accumulator-only rotates, with X and Y copied
+/// from the new accumulator.
+#[inline]
+fn acc_flags(cpu: &mut Z80, carry: bool) {
+    cpu.set_flag(Flag::H, false);
+    cpu.set_flag(Flag::N, false);
+    cpu.set_flag(Flag::C, carry);
+    xy_flags(cpu, cpu.reg(Register::A) as u8);
+}
+
+#[inline]
+pub fn rlc(cpu: &mut Z80, val: u8) -> u8 {
+    let res = val.rotate_left(1);
+    shift_flags(cpu, res, val & 0x80 != 0);
+    res
+}
+
+#[inline]
+pub fn rrc(cpu: &mut Z80, val: u8) -> u8 {
+    let res = val.rotate_right(1);
+    shift_flags(cpu, res, val & 0x01 != 0);
+    res
+}
+
+#[inline]
+pub fn rl(cpu: &mut Z80, val: u8) -> u8 {
+    let res = (val << 1) | cpu.flag(Flag::C) as u8;
+    shift_flags(cpu, res, val & 0x80 != 0);
+    res
+}
+
+#[inline]
+pub fn rr(cpu: &mut Z80, val: u8) -> u8 {
+    let res = (val >> 1) | ((cpu.flag(Flag::C) as u8) << 7);
+    shift_flags(cpu, res, val & 0x01 != 0);
+    res
+}
+
+#[inline]
+pub fn sla(cpu: &mut Z80, val: u8) -> u8 {
+    let res = val << 1;
+    shift_flags(cpu, res, val & 0x80 != 0);
+    res
+}
+
+#[inline]
+pub fn sra(cpu: &mut Z80, val: u8) -> u8 {
+    let res = (val >> 1) | (val & 0x80);
+    shift_flags(cpu, res, val & 0x01 != 0);
+    res
+}
+
+#[inline]
+pub fn sll(cpu: &mut Z80, val: u8) -> u8 {
+    let res = (val << 1) | 0x01;
+    shift_flags(cpu, res, val & 0x80 != 0);
+    res
+}
+
+#[inline]
+pub fn srl(cpu: &mut Z80, val: u8) -> u8 {
+    let res = val >> 1;
+    shift_flags(cpu, res, val & 0x01 != 0);
+    res
+}
+
+/// Apply a rotate or shift to a register.
+#[inline]
+pub fn shift_r(cpu: &mut Z80, r: Register, op: ShiftOp) {
+    let val = op(cpu, cpu.reg(r) as u8);
+    cpu.set_reg(r, val as u16);
+}
+
+/// Apply a rotate or shift to a byte of memory, also copying the result into `wb`
+/// if it is given.
+#[inline]
+pub fn shift_m(cpu: &mut Z80, addr: u16, wb: Option<Register>, op: ShiftOp, bus: &mut impl Bus) {
+    let val = op(cpu, bus.read(addr));
+    bus.write(addr, val);
+    if let Some(r) = wb {
+        cpu.set_reg(r, val as u16);
+    }
+}
+
+#[inline]
+pub fn rlca(cpu: &mut Z80) {
+    let a = cpu.reg(Register::A) as u8;
+    cpu.set_reg(Register::A, a.rotate_left(1) as u16);
+    acc_flags(cpu, a & 0x80 != 0);
+}
+
+#[inline]
+pub fn rrca(cpu: &mut Z80) {
+    let a = cpu.reg(Register::A) as u8;
+    cpu.set_reg(Register::A, a.rotate_right(1) as u16);
+    acc_flags(cpu, a & 0x01 != 0);
+}
+
+#[inline]
+pub fn rla(cpu: &mut Z80) {
+    let a = cpu.reg(Register::A) as u8;
+    let res = (a << 1) | cpu.flag(Flag::C) as u8;
+    cpu.set_reg(Register::A, res as u16);
+    acc_flags(cpu, a & 0x80 != 0);
+}
+
+#[inline]
+pub fn rra(cpu: &mut Z80) {
+    let a = cpu.reg(Register::A) as u8;
+    let res = (a >> 1) | ((cpu.flag(Flag::C) as u8) << 7);
+    cpu.set_reg(Register::A, res as u16);
+    acc_flags(cpu, a & 0x01 != 0);
+}
+
+/// Set the flags affected by `RLD` and `RRD`.
+#[inline]
+fn digit_flags(cpu: &mut Z80, a: u8) {
+    cpu.set_flag(Flag::S, (a as i8) < 0);
+    cpu.set_flag(Flag::Z, a == 0);
+    cpu.set_flag(Flag::H, false);
+    cpu.set_flag(Flag::PV, parity(a));
+    cpu.set_flag(Flag::N, false);
+    xy_flags(cpu, a);
+}
+
+#[inline]
+pub fn rld(cpu: &mut Z80, bus: &mut impl Bus) {
+    let addr = cpu.hl;
+    let a = cpu.reg(Register::A) as u8;
+    let m = bus.read(addr);
+    bus.write(addr, (m << 4) | (a & 0x0f));
+    cpu.memptr = cpu.hl.wrapping_add(1);
+    let a = (a & 0xf0) | (m >> 4);
+    cpu.set_reg(Register::A, a as u16);
+    digit_flags(cpu, a);
+}
+
+#[inline]
+pub fn rrd(cpu: &mut Z80, bus: &mut impl Bus) {
+    let addr = cpu.hl;
+    let a = cpu.reg(Register::A) as u8;
+    let m = bus.read(addr);
+    bus.write(addr, (a << 4) | (m >> 4));
+    cpu.memptr = cpu.hl.wrapping_add(1);
+    let a = (a & 0xf0) | (m & 0x0f);
+    cpu.set_reg(Register::A, a as u16);
+    digit_flags(cpu, a);
+}
+
+#[cfg(test)]
+mod rotate_tests {
+    use super::*;
+    use crate::bus::Ram;
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[rstest]
+    #[case::rlc(rlc as ShiftOp, 0x81, 0x03)]
+    #[case::rrc(rrc as ShiftOp, 0x81, 0xc0)]
+    #[case::rl(rl as ShiftOp, 0x81, 0x02)]
+    #[case::rr(rr as ShiftOp, 0x81, 0x40)]
+    #[case::sla(sla as ShiftOp, 0x81, 0x02)]
+    #[case::sra(sra as ShiftOp, 0x81, 0xc0)]
+    #[case::sll(sll as ShiftOp, 0x81, 0x03)]
+    #[case::srl(srl as ShiftOp, 0x81, 0x40)]
+    fn test_shift_r(mut z80: Z80, #[case] op: ShiftOp, #[case] val: u8, #[case] expected: u8) {
+        z80.set_reg(Register::B, val as u16);
+        shift_r(&mut z80, Register::B, op);
+        assert_eq!(expected as u16, z80.reg(Register::B));
+        assert_eq!(parity(expected), z80.flag(Flag::PV));
+        assert!(!z80.flag(Flag::H));
+        assert!(!z80.flag(Flag::N));
+        assert!(z80.flag(Flag::C));
+        assert_eq!(expected >= 0x80, z80.flag(Flag::S));
+        assert!(!z80.flag(Flag::Z));
+    }
+
+    #[rstest]
+    #[case::x(0x04, 0x08)]
+    #[case::y(0x10, 0x20)]
+    #[case::none(0x41, 0x00)]
+    fn test_shift_xy(mut z80: Z80, #[case] val: u8, #[case] xy: u8) {
+        z80.set_reg(Register::B, val as u16);
+        shift_r(&mut z80, Register::B, sla);
+        assert_eq!(xy, z80.reg(Register::F) as u8 & 0x28);
+    }
+
+    #[rstest]
+    fn test_shift_m_writes_back(mut z80: Z80) {
+        let mut mem = Ram::new();
+        mem[..2].copy_from_slice(&[0x01, 0x80]);
+        shift_m(&mut z80, 1, Some(Register::E), srl, &mut mem);
+        assert_eq!(0x40, mem[1]);
+        assert_eq!(0x40, z80.reg(Register::E));
+        shift_m(&mut z80, 0, None, sla, &mut mem);
+        assert_eq!(0x02, mem[0]);
+        assert_eq!(0x40, z80.reg(Register::E));
+    }
+
+    #[rstest]
+    fn test_rlca_rrca(mut z80: Z80) {
+        z80.set_reg(Register::A, 0x81);
+        rlca(&mut z80);
+        assert_eq!(0x03, z80.reg(Register::A));
+        rrca(&mut z80);
+        rrca(&mut z80);
+        assert_eq!(0xc0, z80.reg(Register::A));
+    }
+
+    #[rstest]
+    fn test_rla_rra(mut z80: Z80) {
+        z80.set_reg(Register::A, 0x41);
+        rla(&mut z80);
+        assert_eq!(0x82, z80.reg(Register::A));
+        rra(&mut z80);
+        assert_eq!(0x41, z80.reg(Register::A));
+    }
+
+    #[rstest]
+    fn test_rla_rra_carry(mut z80: Z80) {
+        z80.set_reg(Register::A, 0x80);
+        rla(&mut z80);
+        assert_eq!(0x00, z80.reg(Register::A));
+        assert!(z80.flag(Flag::C));
+        rla(&mut z80);
+        assert_eq!(0x01, z80.reg(Register::A));
+        assert!(!z80.flag(Flag::C));
+        rra(&mut z80);
+        assert_eq!(0x00, z80.reg(Register::A));
+        assert!(z80.flag(Flag::C));
+        rra(&mut z80);
+        assert_eq!(0x80, z80.reg(Register::A));
+        assert!(!z80.flag(Flag::C));
+    }
+
+    #[rstest]
+    fn test_rld(mut z80: Z80) {
+        let mut mem = Ram::new();
+        mem[0] = 0x31;
+        z80.set_reg(Register::A, 0x7a);
+        rld(&mut z80, &mut mem);
+        assert_eq!(0x73, z80.reg(Register::A));
+        assert_eq!(0x1a, mem[0]);
+        assert_eq!(0x0001, z80.memptr);
+    }
+
+    #[rstest]
+    fn test_rrd(mut z80: Z80) {
+        let mut mem = Ram::new();
+        mem[0] = 0x20;
+        z80.set_reg(Register::A, 0x84);
+        rrd(&mut z80, &mut mem);
+        assert_eq!(0x80, z80.reg(Register::A));
+        assert_eq!(0x42, mem[0]);
+    }
+}
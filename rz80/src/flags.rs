@@ -0,0 +1,177 @@
+//! Types for reading and writing the status flags held in the `F` register.
+use std::fmt;
+
+/// Enums for identifying different status flags.
+///
+/// Each value is the mask of the flag's bit within the `F` register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Flag {
+    /// Carry flag
+    C = 0b0000_0001,
+    /// Add/subtract flag
+    N = 0b0000_0010,
+    /// Parity/overflow flag
+    PV = 0b0000_0100,
+    /// Undocumented copy of bit 3 of the result
+    X = 0b0000_1000,
+    /// Half-carry flag
+    H = 0b0001_0000,
+    /// Undocumented copy of bit 5 of the result
+    Y = 0b0010_0000,
+    /// Zero flag
+    Z = 0b0100_0000,
+    /// Sign flag
+    S = 0b1000_0000,
+}
+
+impl Flag {
+    /// Every flag, ordered from bit 7 down to bit 0.
+    pub const ALL: [Flag; 8] = [
+        Flag::S,
+        Flag::Z,
+        Flag::Y,
+        Flag::H,
+        Flag::X,
+        Flag::PV,
+        Flag::N,
+        Flag::C,
+    ];
+
+    /// Returns the mask of this flag's bit within the `F` register.
+    pub const fn mask(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the letter used for this flag when displaying a [`Flags`] value.
+    pub const fn symbol(self) -> char {
+        match self {
+            Flag::C => 'C',
+            Flag::N => 'N',
+            Flag::PV => 'P',
+            Flag::X => 'X',
+            Flag::H => 'H',
+            Flag::Y => 'Y',
+            Flag::Z => 'Z',
+            Flag::S => 'S',
+        }
+    }
+}
+
+/// Typed view of the `F` register.
+///
+/// # Example
+/// ```
+/// # use rz80::{Flag, Flags};
+/// let mut flags = Flags::from_bits(0x00);
+/// flags.set(Flag::C, true);
+/// flags.set(Flag::Z, true);
+/// assert!(flags.get(Flag::C));
+/// assert_eq!(0x41, flags.bits());
+/// assert_eq!("-Z-----C", flags.to_string());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Flags(u8);
+
+impl Flags {
+    /// Create a flags value from the raw contents of the `F` register.
+    pub const fn from_bits(bits: u8) -> Self {
+        Flags(bits)
+    }
+
+    /// Returns the raw contents of the `F` register.
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Return whether the given flag is set.
+    ///
+    /// # Arguments
+    /// - `f`: flag to check
+    pub const fn get(self, f: Flag) -> bool {
+        self.0 & f.mask() != 0
+    }
+
+    /// Set or clear the given flag.
+    ///
+    /// # Arguments
+    /// - `f`: flag to set
+    /// - `val`: value to set the flag to
+    pub fn set(&mut self, f: Flag, val: bool) {
+        if val {
+            self.0 |= f.mask();
+        } else {
+            self.0 &= !f.mask();
+        }
+    }
+}
+
+impl From<u8> for Flags {
+    fn from(bits: u8) -> Self {
+        Flags(bits)
+    }
+}
+
+impl From<Flags> for u8 {
+    fn from(flags: Flags) -> Self {
+        flags.0
+    }
+}
+
+/// Formats the flags from bit 7 down to bit 0, using each flag's letter if it is set
+/// and `-` otherwise, e.g. `SZ-H-PNC`.
+impl fmt::Display for Flags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for flag in Flag::ALL {
+            let c = if self.get(flag) { flag.symbol() } else { '-' };
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod flags_tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case::c(Flag::C, 0x01)]
+    #[case::n(Flag::N, 0x02)]
+    #[case::pv(Flag::PV, 0x04)]
+    #[case::x(Flag::X, 0x08)]
+    #[case::h(Flag::H, 0x10)]
+    #[case::y(Flag::Y, 0x20)]
+    #[case::z(Flag::Z, 0x40)]
+    #[case::s(Flag::S, 0x80)]
+    fn test_set_get(#[case] flag: Flag, #[case] mask: u8) {
+        let mut flags = Flags::default();
+        flags.set(flag, true);
+        assert_eq!(mask, flags.bits());
+        assert!(flags.get(flag));
+        for other in Flag::ALL.into_iter().filter(|f| *f != flag) {
+            assert!(!flags.get(other));
+        }
+
+        let mut flags = Flags::from_bits(0xff);
+        flags.set(flag, false);
+        assert_eq!(!mask, flags.bits());
+        assert!(!flags.get(flag));
+    }
+
+    #[rstest]
+    #[case::none(0x00, "--------")]
+    #[case::all(0xff, "SZYHXPNC")]
+    #[case::documented(0xd7, "SZ-H-PNC")]
+    #[case::carry(0x01, "-------C")]
+    fn test_display(#[case] bits: u8, #[case] expected: &str) {
+        assert_eq!(expected, Flags::from_bits(bits).to_string());
+    }
+
+    #[rstest]
+    fn test_conversions() {
+        let flags: Flags = 0xa5.into();
+        assert_eq!(Flags::from_bits(0xa5), flags);
+        assert_eq!(0xa5, u8::from(flags));
+    }
+}
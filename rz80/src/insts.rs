@@ -5,6 +5,7 @@ use super::{Register, Condition};
 /// 
 /// Instructions implement `Copy` so there is no need to worry about passing references.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(non_camel_case_types)]
 pub enum Instruction {
     // 8-Bit Load
@@ -50,6 +51,47 @@ pub enum Instruction {
     LD_I_A,
     /// `LD R, A`
     LD_R_A,
+    // 16-bit Load
+    /// `LD dd, nn`
+    LD_dd_nn(Register, u16),
+    /// `LD IX, nn`
+    LD_IX_nn(u16),
+    /// `LD IY, nn`
+    LD_IY_nn(u16),
+    /// `LD HL, (nn)`
+    LD_HL_inn(u16),
+    /// `LD dd, (nn)`
+    LD_dd_inn(Register, u16),
+    /// `LD IX, (nn)`
+    LD_IX_inn(u16),
+    /// `LD IY, (nn)`
+    LD_IY_inn(u16),
+    /// `LD (nn), HL`
+    LD_nn_HL(u16),
+    /// `LD (nn), dd`
+    LD_nn_dd(u16, Register),
+    /// `LD (nn), IX`
+    LD_nn_IX(u16),
+    /// `LD (nn), IY`
+    LD_nn_IY(u16),
+    /// `LD SP, HL`
+    LD_SP_HL,
+    /// `LD SP, IX`
+    LD_SP_IX,
+    /// `LD SP, IY`
+    LD_SP_IY,
+    /// `PUSH qq`
+    PUSH_qq(Register),
+    /// `PUSH IX`
+    PUSH_IX,
+    /// `PUSH IY`
+    PUSH_IY,
+    /// `POP qq`
+    POP_qq(Register),
+    /// `POP IX`
+    POP_IX,
+    /// `POP IY`
+    POP_IY,
     // Exchange and Transfer
     /// `EX DE, HL`
     EX_DE_HL,
@@ -177,12 +219,161 @@ pub enum Instruction {
     DEC_IX(i8),
     /// `DEC (IY+d)`
     DEC_IY(i8),
+    // 16-bit Arithmetic
+    /// `ADD HL, ss`
+    ADD_HL_ss(Register),
+    /// `ADC HL, ss`
+    ADC_HL_ss(Register),
+    /// `SBC HL, ss`
+    SBC_HL_ss(Register),
+    /// `ADD IX, pp`
+    ADD_IX_pp(Register),
+    /// `ADD IY, rr`
+    ADD_IY_rr(Register),
+    /// `INC ss`, also covering `INC IX` and `INC IY`
+    INC_ss(Register),
+    /// `DEC ss`, also covering `DEC IX` and `DEC IY`
+    DEC_ss(Register),
+    // Rotate and Shift
+    /// `RLCA`
+    RLCA,
+    /// `RLA`
+    RLA,
+    /// `RRCA`
+    RRCA,
+    /// `RRA`
+    RRA,
+    /// `RLC r`
+    RLC_r(Register),
+    /// `RLC (HL)`
+    RLC_HL,
+    /// `RLC (IX+d)`, optionally also copying the result to a register (undocumented)
+    RLC_IX(i8, Option<Register>),
+    /// `RLC (IY+d)`, optionally also copying the result to a register (undocumented)
+    RLC_IY(i8, Option<Register>),
+    /// `RL r`
+    RL_r(Register),
+    /// `RL (HL)`
+    RL_HL,
+    /// `RL (IX+d)`, optionally also copying the result to a register (undocumented)
+    RL_IX(i8, Option<Register>),
+    /// `RL (IY+d)`, optionally also copying the result to a register (undocumented)
+    RL_IY(i8, Option<Register>),
+    /// `RRC r`
+    RRC_r(Register),
+    /// `RRC (HL)`
+    RRC_HL,
+    /// `RRC (IX+d)`, optionally also copying the result to a register (undocumented)
+    RRC_IX(i8, Option<Register>),
+    /// `RRC (IY+d)`, optionally also copying the result to a register (undocumented)
+    RRC_IY(i8, Option<Register>),
+    /// `RR r`
+    RR_r(Register),
+    /// `RR (HL)`
+    RR_HL,
+    /// `RR (IX+d)`, optionally also copying the result to a register (undocumented)
+    RR_IX(i8, Option<Register>),
+    /// `RR (IY+d)`, optionally also copying the result to a register (undocumented)
+    RR_IY(i8, Option<Register>),
+    /// `SLA r`
+    SLA_r(Register),
+    /// `SLA (HL)`
+    SLA_HL,
+    /// `SLA (IX+d)`, optionally also copying the result to a register (undocumented)
+    SLA_IX(i8, Option<Register>),
+    /// `SLA (IY+d)`, optionally also copying the result to a register (undocumented)
+    SLA_IY(i8, Option<Register>),
+    /// `SRA r`
+    SRA_r(Register),
+    /// `SRA (HL)`
+    SRA_HL,
+    /// `SRA (IX+d)`, optionally also copying the result to a register (undocumented)
+    SRA_IX(i8, Option<Register>),
+    /// `SRA (IY+d)`, optionally also copying the result to a register (undocumented)
+    SRA_IY(i8, Option<Register>),
+    /// `SLL r` (undocumented)
+    SLL_r(Register),
+    /// `SLL (HL)` (undocumented)
+    SLL_HL,
+    /// `SLL (IX+d)`, optionally also copying the result to a register (undocumented)
+    SLL_IX(i8, Option<Register>),
+    /// `SLL (IY+d)`, optionally also copying the result to a register (undocumented)
+    SLL_IY(i8, Option<Register>),
+    /// `SRL r`
+    SRL_r(Register),
+    /// `SRL (HL)`
+    SRL_HL,
+    /// `SRL (IX+d)`, optionally also copying the result to a register (undocumented)
+    SRL_IX(i8, Option<Register>),
+    /// `SRL (IY+d)`, optionally also copying the result to a register (undocumented)
+    SRL_IY(i8, Option<Register>),
+    /// `RLD`
+    RLD,
+    /// `RRD`
+    RRD,
+    // Bit Set, Reset, and Test
+    /// `BIT b, r`
+    BIT_b_r(u8, Register),
+    /// `BIT b, (HL)`
+    BIT_b_HL(u8),
+    /// `BIT b, (IX+d)`
+    BIT_b_IX(u8, i8),
+    /// `BIT b, (IY+d)`
+    BIT_b_IY(u8, i8),
+    /// `SET b, r`
+    SET_b_r(u8, Register),
+    /// `SET b, (HL)`
+    SET_b_HL(u8),
+    /// `SET b, (IX+d)`, optionally also copying the result to a register (undocumented)
+    SET_b_IX(u8, i8, Option<Register>),
+    /// `SET b, (IY+d)`, optionally also copying the result to a register (undocumented)
+    SET_b_IY(u8, i8, Option<Register>),
+    /// `RES b, r`
+    RES_b_r(u8, Register),
+    /// `RES b, (HL)`
+    RES_b_HL(u8),
+    /// `RES b, (IX+d)`, optionally also copying the result to a register (undocumented)
+    RES_b_IX(u8, i8, Option<Register>),
+    /// `RES b, (IY+d)`, optionally also copying the result to a register (undocumented)
+    RES_b_IY(u8, i8, Option<Register>),
+    // General-Purpose Arithmetic and CPU Control
+    /// `DAA`
+    DAA,
+    /// `CPL`
+    CPL,
+    /// `NEG`
+    NEG,
+    /// `CCF`
+    CCF,
+    /// `SCF`
+    SCF,
+    /// `NOP`
+    NOP,
+    /// A `DD` or `FD` prefix with no effect on the following opcode, run as a `NOP`
+    PREFIX_NOP(u8),
+    /// An undefined `ED` opcode, run as a two-byte `NOP`
+    ED_NOP(u8),
+    /// `HALT`
+    HALT,
+    /// `DI`
+    DI,
+    /// `EI`
+    EI,
+    /// `IM 0`
+    IM_0,
+    /// `IM 1`
+    IM_1,
+    /// `IM 2`
+    IM_2,
     // Jump
     /// `JP nn`
     JP_nn(u16),
     /// `JP cc, nn`
     JP_cc_nn(Condition, u16),
     /// `JR e`
+    ///
+    /// The operand of this and the conditional forms is the displacement from the
+    /// address of the next instruction.
     JR_e(i8),
     /// `JR C, e`
     JR_C_e(i8),
@@ -198,6 +389,50 @@ pub enum Instruction {
     JP_IX,
     /// `JP (IY)`
     JP_IY,
-    /// `DJNZ e`
-    DJNZ_e(i8)
+    /// `DJNZ e`, with the same displacement as [`Instruction::JR_e`]
+    DJNZ_e(i8),
+    // Call and Return
+    /// `CALL nn`
+    CALL_nn(u16),
+    /// `CALL cc, nn`
+    CALL_cc_nn(Condition, u16),
+    /// `RET`
+    RET,
+    /// `RET cc`
+    RET_cc(Condition),
+    /// `RETI`
+    RETI,
+    /// `RETN`
+    RETN,
+    /// `RST p`
+    RST_p(u8),
+    // Input and Output
+    /// `IN A, (n)`
+    IN_A_n(u8),
+    /// `IN r, (C)`
+    IN_r_C(Register),
+    /// `IN (C)`, setting only the flags (undocumented)
+    IN_C,
+    /// `INI`
+    INI,
+    /// `INIR`
+    INIR,
+    /// `IND`
+    IND,
+    /// `INDR`
+    INDR,
+    /// `OUT (n), A`
+    OUT_n_A(u8),
+    /// `OUT (C), r`
+    OUT_C_r(Register),
+    /// `OUT (C), 0` (undocumented)
+    OUT_C_0,
+    /// `OUTI`
+    OUTI,
+    /// `OTIR`
+    OTIR,
+    /// `OUTD`
+    OUTD,
+    /// `OTDR`
+    OTDR,
 }
\ No newline at end of file
@@ -0,0 +1,307 @@
+//! Methods for raising and accepting maskable and non-maskable interrupts.
+use super::{bus::Bus, execute::load16::push, InterruptMode, Z80};
+
+/// Address jumped to when a non-maskable interrupt is accepted.
+const NMI_VECTOR: u16 = 0x0066;
+/// Address jumped to when a maskable interrupt is accepted in mode 1.
+const IM1_VECTOR: u16 = 0x0038;
+
+impl Z80 {
+    /// Hold the maskable interrupt (`INT`) line active.
+    ///
+    /// The interrupt is accepted at the end of the next instruction for which `IFF1`
+    /// is set, and remains asserted until [`Z80::release_int`] is called.
+    ///
+    /// # Arguments
+    /// - `data`: value the interrupting device places on the data bus
+    pub fn assert_int(&mut self, data: u8) {
+        self.int_line = Some(data);
+    }
+
+    /// Release the maskable interrupt (`INT`) line.
+    pub fn release_int(&mut self) {
+        self.int_line = None;
+    }
+
+    /// Pulse the non-maskable interrupt (`NMI`) line.
+    ///
+    /// The interrupt is latched and accepted at the end of the current instruction,
+    /// regardless of the interrupt enable flip-flops.
+    pub fn pulse_nmi(&mut self) {
+        self.nmi_pending = true;
+    }
+
+    /// Accept a pending interrupt, if any, and return whether one was accepted.
+    ///
+    /// This should be called between instructions. A non-maskable interrupt takes
+    /// priority over a maskable one. A maskable interrupt is only accepted if `IFF1`
+    /// is set and the previous instruction was not `EI`. Neither is accepted straight
+    /// after an ignored `DD` or `FD` prefix, which belongs to the opcode after it.
+    /// The T-states taken to accept the interrupt are added to [`Z80::cycles`], and
+    /// the acknowledge cycle increments `R` like any other M1 cycle.
+    ///
+    /// # Arguments
+    /// - `bus`: the memory and I/O ports available to the CPU
+    ///
+    /// # Example
+    /// ```
+    /// # use rz80::{bus::Ram, InterruptMode, Z80};
+    /// let mut cpu: Z80 = Default::default();
+    /// let mut bus = Ram::new();
+    /// cpu.stack_ptr = 0x8000;
+    /// cpu.iff1 = true;
+    /// cpu.interrupt_mode = InterruptMode::IM1;
+    /// cpu.assert_int(0xff);
+    /// assert!(cpu.handle_interrupts(&mut bus));
+    /// assert_eq!(0x0038, cpu.prog_counter);
+    /// assert_eq!(13, cpu.cycles);
+    /// ```
+    pub fn handle_interrupts(&mut self, bus: &mut impl Bus) -> bool {
+        self.accept_interrupt(bus).is_some()
+    }
+
+    /// Accept a pending interrupt, if any, and return the T-states taken to do so.
+    ///
+    /// In IM 0 this includes the T-states of the instruction supplied by the device.
+    pub(crate) fn accept_interrupt(&mut self, bus: &mut impl Bus) -> Option<u32> {
+        if self.prefix_pending {
+            return None;
+        }
+
+        if self.nmi_pending {
+            self.nmi_pending = false;
+            self.accept_nmi(bus);
+            self.cycles += 11;
+            return Some(11);
+        }
+
+        let data = match self.int_line {
+            Some(data) if self.iff1 && !self.ei_pending => data,
+            _ => return None,
+        };
+
+        self.leave_halt();
+        self.inc_refresh();
+        self.iff1 = false;
+        self.iff2 = false;
+        let t = match self.interrupt_mode {
+            InterruptMode::IM0 => {
+                // The device keeps supplying `data` for any operand bytes
+                let t = match self.decode(&[data; 4]) {
+                    Ok((inst, _)) => self.execute(inst, bus),
+                    Err(_) => 0,
+                };
+                // Acknowledging the interrupt adds two wait states to the M1 cycle
+                self.cycles += 2;
+                t + 2
+            }
+            InterruptMode::IM1 => {
+                self.call_vector(IM1_VECTOR, bus);
+                self.cycles += 13;
+                13
+            }
+            InterruptMode::IM2 => {
+                let table = ((self.interrupt as u16) << 8) | data as u16;
+                let lo = bus.read(table) as u16;
+                let hi = bus.read(table.wrapping_add(1)) as u16;
+                self.call_vector((hi << 8) | lo, bus);
+                self.cycles += 19;
+                19
+            }
+        };
+        Some(t)
+    }
+
+    /// Accept a non-maskable interrupt, saving `IFF1` in `IFF2`.
+    fn accept_nmi(&mut self, bus: &mut impl Bus) {
+        self.leave_halt();
+        self.inc_refresh();
+        self.iff2 = self.iff1;
+        self.iff1 = false;
+        self.call_vector(NMI_VECTOR, bus);
+    }
+
+    /// Push the program counter and jump to `addr`.
+    fn call_vector(&mut self, addr: u16, bus: &mut impl Bus) {
+        push(self, self.prog_counter, bus);
+        self.prog_counter = addr;
+        self.memptr = addr;
+    }
+
+    /// Resume execution after a `HALT`, so that the return address follows it.
+    fn leave_halt(&mut self) {
+        if self.halted {
+            self.halted = false;
+            self.prog_counter = self.prog_counter.wrapping_add(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod interrupt_tests {
+    use super::*;
+    use crate::{bus::Ram, Instruction, Register};
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Z80 {
+            prog_counter: 0x1234,
+            stack_ptr: 0x8000,
+            iff1: true,
+            iff2: true,
+            ..Default::default()
+        }
+    }
+
+    #[fixture]
+    fn mem() -> Ram {
+        Ram::new()
+    }
+
+    #[rstest]
+    fn test_im0_executes_bus_opcode(mut z80: Z80, mut mem: Ram) {
+        z80.interrupt_mode = InterruptMode::IM0;
+        z80.assert_int(0xef);
+        assert!(z80.handle_interrupts(&mut mem));
+        assert_eq!(0x0028, z80.prog_counter);
+        assert_eq!(0x34, mem[0x7ffe]);
+        assert_eq!(0x12, mem[0x7fff]);
+        assert!(!z80.iff1 && !z80.iff2);
+        assert_eq!(13, z80.cycles);
+    }
+
+    #[rstest]
+    fn test_im1(mut z80: Z80, mut mem: Ram) {
+        z80.interrupt_mode = InterruptMode::IM1;
+        z80.assert_int(0x00);
+        assert!(z80.handle_interrupts(&mut mem));
+        assert_eq!(0x0038, z80.prog_counter);
+        assert_eq!(0x7ffe, z80.stack_ptr);
+        assert_eq!(13, z80.cycles);
+        assert_eq!(1, z80.refresh);
+    }
+
+    #[rstest]
+    fn test_im2(mut z80: Z80, mut mem: Ram) {
+        z80.interrupt_mode = InterruptMode::IM2;
+        z80.interrupt = 0x3b;
+        mem[0x3bff] = 0xcd;
+        mem[0x3c00] = 0xab;
+        z80.assert_int(0xff);
+        assert!(z80.handle_interrupts(&mut mem));
+        assert_eq!(0xabcd, z80.prog_counter);
+        assert_eq!(0x34, mem[0x7ffe]);
+        assert_eq!(19, z80.cycles);
+    }
+
+    #[rstest]
+    fn test_int_disabled(mut z80: Z80, mut mem: Ram) {
+        z80.iff1 = false;
+        z80.assert_int(0xff);
+        assert!(!z80.handle_interrupts(&mut mem));
+        assert_eq!(0x1234, z80.prog_counter);
+    }
+
+    #[rstest]
+    fn test_int_released(mut z80: Z80, mut mem: Ram) {
+        z80.assert_int(0xff);
+        z80.release_int();
+        assert!(!z80.handle_interrupts(&mut mem));
+    }
+
+    #[rstest]
+    fn test_ei_delay(mut z80: Z80, mut mem: Ram) {
+        z80.iff1 = false;
+        z80.interrupt_mode = InterruptMode::IM1;
+        z80.assert_int(0xff);
+        z80.execute(Instruction::EI, &mut mem);
+        assert!(!z80.handle_interrupts(&mut mem));
+        z80.execute(Instruction::NOP, &mut mem);
+        assert!(z80.handle_interrupts(&mut mem));
+        assert_eq!(0x0038, z80.prog_counter);
+    }
+
+    #[rstest]
+    fn test_int_releases_halt(mut z80: Z80, mut mem: Ram) {
+        z80.interrupt_mode = InterruptMode::IM1;
+        z80.prog_counter = 0x1235;
+        z80.execute(Instruction::HALT, &mut mem);
+        assert_eq!(0x1234, z80.prog_counter);
+        z80.assert_int(0xff);
+        assert!(z80.handle_interrupts(&mut mem));
+        assert!(!z80.halted);
+        assert_eq!(0x35, mem[0x7ffe]);
+        assert_eq!(0x12, mem[0x7fff]);
+    }
+
+    #[rstest]
+    fn test_nmi(mut z80: Z80, mut mem: Ram) {
+        z80.iff2 = false;
+        z80.pulse_nmi();
+        assert!(z80.handle_interrupts(&mut mem));
+        assert_eq!(NMI_VECTOR, z80.prog_counter);
+        assert!(!z80.iff1);
+        assert!(z80.iff2);
+        assert_eq!(11, z80.cycles);
+        assert_eq!(1, z80.refresh);
+        assert!(!z80.handle_interrupts(&mut mem));
+        z80.execute(Instruction::RETN, &mut mem);
+        assert_eq!(0x1234, z80.prog_counter);
+        assert!(z80.iff1);
+    }
+
+    #[rstest]
+    fn test_nmi_ignores_iff1(mut z80: Z80, mut mem: Ram) {
+        z80.iff1 = false;
+        z80.pulse_nmi();
+        z80.assert_int(0xff);
+        assert!(z80.handle_interrupts(&mut mem));
+        assert_eq!(NMI_VECTOR, z80.prog_counter);
+    }
+
+    #[rstest]
+    fn test_int_waits_after_prefix(mut z80: Z80, mut mem: Ram) {
+        z80.interrupt_mode = InterruptMode::IM1;
+        mem[0x1234..0x1236].copy_from_slice(&[0xdd, 0x41]);
+        z80.assert_int(0xff);
+        let step = z80.step(&mut mem).unwrap();
+        assert_eq!(Instruction::PREFIX_NOP(0xdd), step.instruction);
+        assert_eq!(0x1235, z80.prog_counter);
+        assert!(z80.iff1);
+        let step = z80.step(&mut mem).unwrap();
+        assert_eq!(
+            Instruction::LD_r_r(Register::B, Register::C),
+            step.instruction
+        );
+        assert_eq!(0x0038, z80.prog_counter);
+        assert_eq!(0x36, mem[0x7ffe]);
+        assert_eq!(0x12, mem[0x7fff]);
+    }
+
+    #[rstest]
+    fn test_nmi_waits_after_prefix(mut z80: Z80, mut mem: Ram) {
+        mem[0x1234..0x1237].copy_from_slice(&[0xfd, 0xfd, 0x00]);
+        z80.pulse_nmi();
+        z80.step(&mut mem).unwrap();
+        z80.step(&mut mem).unwrap();
+        assert_eq!(0x1236, z80.prog_counter);
+        assert!(z80.nmi_pending);
+        let step = z80.step(&mut mem).unwrap();
+        assert_eq!(Instruction::NOP, step.instruction);
+        assert_eq!(NMI_VECTOR, z80.prog_counter);
+        assert_eq!(0x37, mem[0x7ffe]);
+        assert_eq!(0x12, mem[0x7fff]);
+    }
+
+    #[rstest]
+    #[case::enabled(true)]
+    #[case::disabled(false)]
+    fn test_ld_a_i_copies_iff2(mut z80: Z80, mut mem: Ram, #[case] iff2: bool) {
+        z80.iff2 = iff2;
+        z80.interrupt = 0x3b;
+        z80.execute(Instruction::LD_A_I, &mut mem);
+        assert_eq!(0x3b, z80.reg(Register::A));
+        assert_eq!(iff2, z80.flag(crate::Flag::PV));
+    }
+}
@@ -0,0 +1,51 @@
+//! Defines a trait through which the CPU accesses I/O ports.
+
+/// Trait for hosts that provide I/O ports to the CPU.
+///
+/// The CPU reaches the ports through a [`Bus`](crate::bus::Bus), which extends this
+/// trait with memory access.
+///
+/// Every port access receives the full 16-bit address placed on the bus. For
+/// `IN A, (n)` and `OUT (n), A` the high byte is `A`; for every other I/O
+/// instruction it is `B`.
+///
+/// # Example
+/// ```
+/// # use rz80::io::IoBus;
+/// struct Border(u8);
+///
+/// impl IoBus for Border {
+///     fn port_in(&mut self, _port: u16) -> u8 {
+///         0xff
+///     }
+///
+///     fn port_out(&mut self, port: u16, val: u8) {
+///         if port & 0x01 == 0 {
+///             self.0 = val & 0x07;
+///         }
+///     }
+/// }
+/// ```
+pub trait IoBus {
+    /// Read a byte from the given port.
+    ///
+    /// # Arguments
+    /// - `port`: full 16-bit port address
+    fn port_in(&mut self, port: u16) -> u8;
+
+    /// Write a byte to the given port.
+    ///
+    /// # Arguments
+    /// - `port`: full 16-bit port address
+    /// - `val`: value to write
+    fn port_out(&mut self, port: u16, val: u8);
+}
+
+/// An empty bus on which every read returns `0xff` and every write is ignored.
+impl IoBus for () {
+    fn port_in(&mut self, _port: u16) -> u8 {
+        0xff
+    }
+
+    fn port_out(&mut self, _port: u16, _val: u8) {}
+}
@@ -1,19 +1,34 @@
 //! Provides an Zilog Z80 CPU.
+pub mod bus;
 pub mod carry_borrow;
 mod decode;
+pub mod disasm;
 mod execute;
+mod flags;
 pub mod hi_lo;
 mod insts;
+mod interrupt;
+pub mod io;
+mod run;
 
+use bus::Bus;
 use hi_lo::HiLo;
-use std::{thread, time};
+use std::fmt;
+pub use decode::DecodeError;
+pub use flags::{Flag, Flags};
 pub use insts::Instruction;
+pub use run::{Step, StopReason};
 
-/// Constant representing a clock speed of 4KHz.
-const CLOCK_SPEED: time::Duration = time::Duration::from_nanos(1_000_000_000 / 4_000);
+/// Length in bytes of the longest instruction, including prefixes.
+const MAX_INST_LEN: usize = 4;
 
 /// Emulated Z80 CPU
-#[derive(Default)]
+///
+/// With the `serde` feature enabled the CPU can be serialized, including the hidden
+/// state of the interrupt flip-flops, `HALT`, a pending `EI`, MEMPTR and the T-state
+/// count, so a saved CPU resumes exactly where it left off.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Z80 {
     /// Main AF register pair
     pub af: u16,
@@ -43,6 +58,30 @@ pub struct Z80 {
     pub refresh: u8,
     /// Program counter
     pub prog_counter: u16,
+    /// Interrupt enable flip-flop 1, which controls whether maskable interrupts are accepted
+    pub iff1: bool,
+    /// Interrupt enable flip-flop 2, which preserves `iff1` during a non-maskable interrupt
+    pub iff2: bool,
+    /// Current maskable interrupt mode
+    pub interrupt_mode: InterruptMode,
+    /// Whether the CPU is halted, waiting for an interrupt
+    pub halted: bool,
+    /// Value on the data bus while the maskable interrupt line is asserted
+    pub int_line: Option<u8>,
+    /// Whether a non-maskable interrupt has been latched but not yet accepted
+    pub nmi_pending: bool,
+    /// Whether the last instruction was `EI`, delaying acceptance of maskable interrupts
+    pub ei_pending: bool,
+    /// Whether the last instruction was an ignored `DD` or `FD` prefix, delaying
+    /// acceptance of all interrupts until the opcode after it has run
+    pub prefix_pending: bool,
+    /// Internal MEMPTR (WZ) register, visible only through the X and Y flags
+    pub memptr: u16,
+    /// Opcode fetched after an ignored `DD` or `FD` prefix, with its address, which
+    /// begins the next instruction
+    pub prefetched: Option<(u16, u8)>,
+    /// Number of T-states elapsed since the CPU was created
+    pub cycles: u64,
 }
 
 impl Z80 {
@@ -57,6 +96,7 @@ impl Z80 {
         match reg {
             Register::A => self.af.hi() as u16,
             Register::F => self.af.lo() as u16,
+            Register::AF => self.af,
             Register::B => self.bc.hi() as u16,
             Register::C => self.bc.lo() as u16,
             Register::BC => self.bc,
@@ -66,6 +106,20 @@ impl Z80 {
             Register::H => self.hl.hi() as u16,
             Register::L => self.hl.lo() as u16,
             Register::HL => self.hl,
+            Register::IX => self.index_x,
+            Register::IXH => self.index_x.hi() as u16,
+            Register::IXL => self.index_x.lo() as u16,
+            Register::IY => self.index_y,
+            Register::IYH => self.index_y.hi() as u16,
+            Register::IYL => self.index_y.lo() as u16,
+            Register::SP => self.stack_ptr,
+            Register::PC => self.prog_counter,
+            Register::I => self.interrupt as u16,
+            Register::R => self.refresh as u16,
+            Register::AF1 => self.af1,
+            Register::BC1 => self.bc1,
+            Register::DE1 => self.de1,
+            Register::HL1 => self.hl1,
         }
     }
 
@@ -90,6 +144,7 @@ impl Z80 {
         match reg {
             Register::A => self.af.set_hi(val as u8),
             Register::F => self.af.set_lo(val as u8),
+            Register::AF => self.af = val,
             Register::B => self.bc.set_hi(val as u8),
             Register::C => self.bc.set_lo(val as u8),
             Register::BC => self.bc = val,
@@ -99,15 +154,52 @@ impl Z80 {
             Register::H => self.hl.set_hi(val as u8),
             Register::L => self.hl.set_lo(val as u8),
             Register::HL => self.hl = val,
+            Register::IX => self.index_x = val,
+            Register::IXH => self.index_x.set_hi(val as u8),
+            Register::IXL => self.index_x.set_lo(val as u8),
+            Register::IY => self.index_y = val,
+            Register::IYH => self.index_y.set_hi(val as u8),
+            Register::IYL => self.index_y.set_lo(val as u8),
+            Register::SP => self.stack_ptr = val,
+            Register::PC => self.prog_counter = val,
+            Register::I => self.interrupt = val as u8,
+            Register::R => self.refresh = val as u8,
+            Register::AF1 => self.af1 = val,
+            Register::BC1 => self.bc1 = val,
+            Register::DE1 => self.de1 = val,
+            Register::HL1 => self.hl1 = val,
         }
     }
 
+    /// Returns all of the status flags held in the `F` register.
+    pub fn flags(&self) -> Flags {
+        Flags::from_bits(self.af.lo())
+    }
+
+    /// Replace all of the status flags held in the `F` register.
+    ///
+    /// # Arguments
+    /// - `flags`: new value of the flags
+    ///
+    /// # Example
+    /// ```
+    /// # use rz80::{Z80, Flag, Flags};
+    /// # let mut z80: Z80 = Default::default();
+    /// z80.set_flags(Flags::from_bits(0x81));
+    /// assert!(z80.flag(Flag::S));
+    /// assert!(z80.flag(Flag::C));
+    /// assert_eq!("S------C", z80.flags().to_string());
+    /// ```
+    pub fn set_flags(&mut self, flags: Flags) {
+        self.af.set_lo(flags.bits());
+    }
+
     /// Return whether the given status flag is set or not.
     ///
     /// # Argument
     /// - `f`: flag to check
     pub fn flag(&self, f: Flag) -> bool {
-        self.af.lo() & (f as u8) != 0
+        self.flags().get(f)
     }
 
     /// Set the value of the given status flag.
@@ -126,44 +218,211 @@ impl Z80 {
     /// assert!(!z80.flag(Flag::N));
     /// ```
     pub fn set_flag(&mut self, f: Flag, val: bool) {
-        let mask = f as u8;
-        let old = self.af.lo();
-        let new = if val {
-            old | mask
-        } else {
-            (old ^ mask) & !mask
-        };
-        self.af.set_lo(new);
+        let mut flags = self.flags();
+        flags.set(f, val);
+        self.set_flags(flags);
     }
 
-    /// Return a slice of memory beginning at the current program counter.
+    /// Return whether the given condition holds for the current flags.
     ///
     /// # Arguments
-    /// - `memory`: slice representing the entire memory
-    pub fn fetch<'a>(&self, memory: &'a [u8]) -> &'a [u8] {
-        &memory[self.prog_counter as usize..]
+    /// - `cc`: condition to evaluate
+    ///
+    /// # Example
+    /// ```
+    /// # use rz80::{Condition, Flag, Z80};
+    /// # let mut z80: Z80 = Default::default();
+    /// z80.set_flag(Flag::PV, true);
+    /// assert!(z80.condition(Condition::PE));
+    /// assert!(!z80.condition(Condition::PO));
+    /// ```
+    pub fn condition(&self, cc: Condition) -> bool {
+        match cc {
+            Condition::NZ => !self.flag(Flag::Z),
+            Condition::Z => self.flag(Flag::Z),
+            Condition::NC => !self.flag(Flag::C),
+            Condition::C => self.flag(Flag::C),
+            Condition::PO => !self.flag(Flag::PV),
+            Condition::PE => self.flag(Flag::PV),
+            Condition::P => !self.flag(Flag::S),
+            Condition::M => self.flag(Flag::S),
+        }
     }
 
-    /// Start the cpu running the fetch-decode-execute cycle.
+    /// Fetch and decode the instruction at the current program counter.
+    ///
+    /// Bytes are read from the bus only as far as the decoder needs them. The first
+    /// byte, and the byte following each prefix, are read as opcodes in an M1 cycle;
+    /// the displacement and opcode of a `DD CB` or `FD CB` instruction are ordinary
+    /// reads. Addresses wrap around from `0xFFFF` to `0x0000`, so an instruction may
+    /// straddle the top of memory. Each M1 cycle also increments the refresh register,
+    /// so `R` advances by one for an unprefixed instruction and by two for a prefixed
+    /// one.
     ///
-    /// This method loops infinitely unless an error occurs.
+    /// A `DD` or `FD` prefix is only known to be ignored once the opcode after it has
+    /// been fetched. That opcode is kept in [`Z80::prefetched`] and begins the next
+    /// instruction, so it is not fetched, or counted in `R`, a second time.
     ///
     /// # Arguments
-    /// - `memory`: slice of the entire memory available to the CPU
-    pub fn run(&mut self, memory: &mut [u8]) -> Option<()> {
+    /// - `bus`: the bus from which to read the instruction
+    pub fn fetch(&mut self, bus: &mut impl Bus) -> Result<(Instruction, u8), DecodeError> {
+        let mut bytes = [0; MAX_INST_LEN];
+        let mut len = 0;
+        // Only used if the program counter has not been moved since it was fetched
+        let prefetched = self
+            .prefetched
+            .take()
+            .filter(|&(addr, _)| addr == self.prog_counter);
         loop {
-            let m = self.fetch(memory);
-            let (inst, width) = self.decode(m)?;
-            self.prog_counter += width as u16;
-            self.execute(inst, memory);
-            thread::sleep(CLOCK_SPEED);
+            let addr = self.prog_counter.wrapping_add(len as u16);
+            let m1 = len == 0 || (len == 1 && matches!(bytes[0], 0xcb | 0xed | 0xdd | 0xfd));
+            bytes[len] = match prefetched {
+                Some((_, op)) if len == 0 => op,
+                _ if m1 => {
+                    self.inc_refresh();
+                    bus.fetch_opcode(addr)
+                }
+                _ => bus.read(addr),
+            };
+            len += 1;
+            match self.decode(&bytes[..len]) {
+                Err(DecodeError::Truncated { .. }) if len < MAX_INST_LEN => continue,
+                result => {
+                    if let Ok((Instruction::PREFIX_NOP(_), _)) = result {
+                        self.prefetched = Some((addr, bytes[1]));
+                    }
+                    return result;
+                }
+            }
         }
     }
+
+    /// Count an M1 cycle in the refresh register.
+    ///
+    /// Only the low 7 bits of `R` are incremented; bit 7 keeps whatever was last
+    /// loaded by `LD R,A`.
+    fn inc_refresh(&mut self) {
+        self.refresh = (self.refresh & 0x80) | (self.refresh.wrapping_add(1) & 0x7f);
+    }
+
+    /// Reset the CPU to its state at power-on.
+    ///
+    /// `AF` and `SP` are set to `0xFFFF`. Every other register, including the
+    /// alternate set, `I` and `R`, is cleared, so `PC` is `0x0000`. Interrupts are
+    /// disabled, the interrupt mode is `IM 0`, and any `HALT`, pending `EI` or latched
+    /// NMI is cancelled. The T-state count and the state of the interrupt line,
+    /// which is driven by the host, are kept.
+    ///
+    /// # Example
+    /// ```
+    /// # use rz80::{Register, Z80};
+    /// let mut cpu: Z80 = Default::default();
+    /// cpu.set_reg(Register::BC, 0x1234);
+    /// cpu.reset();
+    /// assert_eq!(0xffff, cpu.reg(Register::AF));
+    /// assert_eq!(0xffff, cpu.reg(Register::SP));
+    /// assert_eq!(0x0000, cpu.reg(Register::BC));
+    /// ```
+    pub fn reset(&mut self) {
+        *self = Z80 {
+            af: 0xffff,
+            stack_ptr: 0xffff,
+            int_line: self.int_line,
+            cycles: self.cycles,
+            ..Default::default()
+        };
+    }
+
+    /// Reset the CPU as the `RESET` line does while it is running.
+    ///
+    /// `PC`, `I` and `R` are cleared, interrupts are disabled, the interrupt mode is
+    /// `IM 0`, and any `HALT`, pending `EI` or latched NMI is cancelled. All other
+    /// registers, including `AF` and `SP`, keep their values.
+    ///
+    /// # Example
+    /// ```
+    /// # use rz80::{Register, Z80};
+    /// let mut cpu: Z80 = Default::default();
+    /// cpu.set_reg(Register::BC, 0x1234);
+    /// cpu.set_reg(Register::PC, 0x8000);
+    /// cpu.soft_reset();
+    /// assert_eq!(0x0000, cpu.reg(Register::PC));
+    /// assert_eq!(0x1234, cpu.reg(Register::BC));
+    /// ```
+    pub fn soft_reset(&mut self) {
+        self.prog_counter = 0;
+        self.interrupt = 0;
+        self.refresh = 0;
+        self.iff1 = false;
+        self.iff2 = false;
+        self.interrupt_mode = InterruptMode::IM0;
+        self.halted = false;
+        self.ei_pending = false;
+        self.prefix_pending = false;
+        self.nmi_pending = false;
+        self.prefetched = None;
+    }
+}
+
+/// Formats the registers as a dump in the usual debugger layout.
+///
+/// # Example
+/// ```
+/// # use rz80::{Register, Z80};
+/// let mut cpu: Z80 = Default::default();
+/// cpu.set_reg(Register::AF, 0x12c1);
+/// cpu.set_reg(Register::SP, 0xfffe);
+/// assert_eq!(
+///     "AF=12C1 BC=0000 DE=0000 HL=0000 IX=0000 IY=0000\n\
+///      AF'=0000 BC'=0000 DE'=0000 HL'=0000 SP=FFFE PC=0000\n\
+///      I=00 R=00 IM=0 IFF1=0 IFF2=0 F=SZ-----C",
+///     cpu.to_string()
+/// );
+/// ```
+impl fmt::Display for Z80 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "AF={:04X} BC={:04X} DE={:04X} HL={:04X} IX={:04X} IY={:04X}",
+            self.reg(Register::AF),
+            self.reg(Register::BC),
+            self.reg(Register::DE),
+            self.reg(Register::HL),
+            self.reg(Register::IX),
+            self.reg(Register::IY),
+        )?;
+        writeln!(
+            f,
+            "AF'={:04X} BC'={:04X} DE'={:04X} HL'={:04X} SP={:04X} PC={:04X}",
+            self.reg(Register::AF1),
+            self.reg(Register::BC1),
+            self.reg(Register::DE1),
+            self.reg(Register::HL1),
+            self.reg(Register::SP),
+            self.reg(Register::PC),
+        )?;
+        let im = match self.interrupt_mode {
+            InterruptMode::IM0 => 0,
+            InterruptMode::IM1 => 1,
+            InterruptMode::IM2 => 2,
+        };
+        write!(
+            f,
+            "I={:02X} R={:02X} IM={} IFF1={} IFF2={} F={}",
+            self.reg(Register::I),
+            self.reg(Register::R),
+            im,
+            self.iff1 as u8,
+            self.iff2 as u8,
+            self.flags(),
+        )
+    }
 }
 
 #[cfg(test)]
 mod z80_tests {
     use super::*;
+    use bus::Ram;
     use rstest::*;
 
     #[fixture]
@@ -171,8 +430,86 @@ mod z80_tests {
         Default::default()
     }
 
+    /// Records which addresses are read as opcodes and which as operands.
+    struct Trace {
+        ram: Ram,
+        m1: Vec<u16>,
+        reads: Vec<u16>,
+    }
+
+    impl Bus for Trace {
+        fn read(&mut self, addr: u16) -> u8 {
+            self.reads.push(addr);
+            self.ram.read(addr)
+        }
+
+        fn write(&mut self, addr: u16, val: u8) {
+            self.ram.write(addr, val);
+        }
+
+        fn fetch_opcode(&mut self, addr: u16) -> u8 {
+            self.m1.push(addr);
+            self.ram.read(addr)
+        }
+    }
+
+    impl io::IoBus for Trace {
+        fn port_in(&mut self, _port: u16) -> u8 {
+            0xff
+        }
+
+        fn port_out(&mut self, _port: u16, _val: u8) {}
+    }
+
+    #[rstest]
+    #[case::immediate(0xfffe, &[0x3e, 0x12], Instruction::LD_r_n(Register::A, 0x12))]
+    #[case::immediate_ext(0xffff, &[0x21, 0x34, 0x12], Instruction::LD_dd_nn(Register::HL, 0x1234))]
+    #[case::prefix(0xffff, &[0xed, 0xb0], Instruction::LDIR)]
+    #[case::indexed(0xfffe, &[0xdd, 0x7e, 0xfd], Instruction::LD_r_IX(Register::A, -3))]
+    #[case::indexed_cb(0xfffd, &[0xfd, 0xcb, 0x02, 0xc6], Instruction::SET_b_IY(0, 2, None))]
+    #[case::relative(0xffff, &[0x18, 0x10], Instruction::JR_e(0x10))]
+    fn test_fetch_wraps(
+        mut z80: Z80,
+        #[case] pc: u16,
+        #[case] bytes: &[u8],
+        #[case] inst: Instruction,
+    ) {
+        let mut mem = Ram::new();
+        for (i, b) in bytes.iter().enumerate() {
+            mem[pc.wrapping_add(i as u16) as usize] = *b;
+        }
+        z80.prog_counter = pc;
+        assert_eq!(Ok((inst, bytes.len() as u8)), z80.fetch(&mut mem));
+    }
+
+    #[rstest]
+    #[case::single(&[0x00, 0xff], &[0x8000], &[])]
+    #[case::immediate(&[0x3e, 0x12], &[0x8000], &[0x8001])]
+    #[case::prefix(&[0xed, 0xb0], &[0x8000, 0x8001], &[])]
+    #[case::indexed(&[0xdd, 0x7e, 0xfd], &[0x8000, 0x8001], &[0x8002])]
+    #[case::indexed_cb(&[0xfd, 0xcb, 0x02, 0xc6], &[0x8000, 0x8001], &[0x8002, 0x8003])]
+    fn test_fetch_cycles(
+        mut z80: Z80,
+        #[case] bytes: &[u8],
+        #[case] m1: &[u16],
+        #[case] reads: &[u16],
+    ) {
+        let mut bus = Trace {
+            ram: Ram::new(),
+            m1: vec![],
+            reads: vec![],
+        };
+        bus.ram[0x8000..0x8000 + bytes.len()].copy_from_slice(bytes);
+        z80.prog_counter = 0x8000;
+        z80.fetch(&mut bus).unwrap();
+        assert_eq!(m1, bus.m1);
+        assert_eq!(reads, bus.reads);
+    }
+
     #[rstest]
     #[case::a(Register::A, 0x01)]
+    #[case::f(Register::F, 0x23)]
+    #[case::af(Register::AF, 0x0123)]
     #[case::b(Register::B, 0x45)]
     #[case::c(Register::C, 0x67)]
     #[case::bc(Register::BC, 0x4567)]
@@ -182,17 +519,43 @@ mod z80_tests {
     #[case::h(Register::H, 0xcd)]
     #[case::l(Register::L, 0xef)]
     #[case::hl(Register::HL, 0xcdef)]
+    #[case::ix(Register::IX, 0x1234)]
+    #[case::ixh(Register::IXH, 0x12)]
+    #[case::ixl(Register::IXL, 0x34)]
+    #[case::iy(Register::IY, 0x5678)]
+    #[case::iyh(Register::IYH, 0x56)]
+    #[case::iyl(Register::IYL, 0x78)]
+    #[case::sp(Register::SP, 0xfffe)]
+    #[case::pc(Register::PC, 0x8000)]
+    #[case::i(Register::I, 0x3f)]
+    #[case::r(Register::R, 0x81)]
+    #[case::af1(Register::AF1, 0x1357)]
+    #[case::bc1(Register::BC1, 0x2468)]
+    #[case::de1(Register::DE1, 0x9bdf)]
+    #[case::hl1(Register::HL1, 0xace0)]
     fn test_get_reg(mut z80: Z80, #[case] rname: Register, #[case] expected: u16) {
         z80.af = 0x0123;
         z80.bc = 0x4567;
         z80.de = 0x89ab;
         z80.hl = 0xcdef;
+        z80.index_x = 0x1234;
+        z80.index_y = 0x5678;
+        z80.stack_ptr = 0xfffe;
+        z80.prog_counter = 0x8000;
+        z80.interrupt = 0x3f;
+        z80.refresh = 0x81;
+        z80.af1 = 0x1357;
+        z80.bc1 = 0x2468;
+        z80.de1 = 0x9bdf;
+        z80.hl1 = 0xace0;
         let r = z80.reg(rname);
         assert_eq!(expected, r);
     }
 
     #[rstest]
     #[case::a(Register::A, 0x01)]
+    #[case::f(Register::F, 0x23)]
+    #[case::af(Register::AF, 0x0123)]
     #[case::b(Register::B, 0x45)]
     #[case::c(Register::C, 0x67)]
     #[case::bc(Register::BC, 0x4567)]
@@ -202,29 +565,312 @@ mod z80_tests {
     #[case::h(Register::H, 0xcd)]
     #[case::l(Register::L, 0xef)]
     #[case::hl(Register::HL, 0xcdef)]
+    #[case::ix(Register::IX, 0x1234)]
+    #[case::ixh(Register::IXH, 0x12)]
+    #[case::ixl(Register::IXL, 0x34)]
+    #[case::iy(Register::IY, 0x5678)]
+    #[case::iyh(Register::IYH, 0x56)]
+    #[case::iyl(Register::IYL, 0x78)]
+    #[case::sp(Register::SP, 0xfffe)]
+    #[case::pc(Register::PC, 0x8000)]
+    #[case::i(Register::I, 0x3f)]
+    #[case::r(Register::R, 0x81)]
+    #[case::af1(Register::AF1, 0x1357)]
+    #[case::bc1(Register::BC1, 0x2468)]
+    #[case::de1(Register::DE1, 0x9bdf)]
+    #[case::hl1(Register::HL1, 0xace0)]
     fn test_set_reg(mut z80: Z80, #[case] rname: Register, #[case] val: u16) {
         z80.set_reg(rname, val);
         assert_eq!(val, z80.reg(rname));
     }
 
+    #[rstest]
+    #[case::unprefixed(&[0x00], 0x00, 0x01)]
+    #[case::cb(&[0xcb, 0x00], 0x00, 0x02)]
+    #[case::ed(&[0xed, 0x5f], 0x10, 0x12)]
+    #[case::dd(&[0xdd, 0x21, 0x34, 0x12], 0x00, 0x02)]
+    #[case::ddcb(&[0xdd, 0xcb, 0x05, 0x06], 0x00, 0x02)]
+    #[case::wraps(&[0x00], 0x7f, 0x00)]
+    #[case::keeps_bit_7(&[0xfd, 0xcb, 0x05, 0x06], 0xfe, 0x80)]
+    fn test_fetch_refresh(mut z80: Z80, #[case] bytes: &[u8], #[case] r: u8, #[case] expected: u8) {
+        let mut mem = Ram::new();
+        mem[..bytes.len()].copy_from_slice(bytes);
+        z80.refresh = r;
+        z80.fetch(&mut mem).unwrap();
+        assert_eq!(expected, z80.refresh);
+    }
+
+    #[rstest]
+    #[case::nops(&[0x00, 0x00, 0xed, 0x5f])]
+    #[case::ignored_prefix(&[0xdd, 0x00, 0xed, 0x5f])]
+    fn test_step_ld_a_r(mut z80: Z80, #[case] bytes: &[u8]) {
+        let mut mem = Ram::new();
+        mem[..4].copy_from_slice(bytes);
+        for _ in 0..3 {
+            z80.step(&mut mem).unwrap();
+        }
+        assert_eq!(0x04, z80.reg(Register::A));
+    }
+
+    #[rstest]
+    #[case::dd(&[0xdd, 0x41], 2)]
+    #[case::fd_fd(&[0xfd, 0xfd, 0x00], 3)]
+    fn test_ignored_prefix_m1(mut z80: Z80, #[case] bytes: &[u8], #[case] steps: usize) {
+        let mut bus = Trace {
+            ram: Ram::new(),
+            m1: vec![],
+            reads: vec![],
+        };
+        bus.ram[0x8000..0x8000 + bytes.len()].copy_from_slice(bytes);
+        z80.prog_counter = 0x8000;
+        for _ in 0..steps {
+            z80.step(&mut bus).unwrap();
+        }
+        let end = 0x8000 + bytes.len() as u16;
+        assert_eq!(end, z80.prog_counter);
+        assert_eq!(bytes.len() as u8, z80.refresh);
+        assert_eq!((0x8000..end).collect::<Vec<u16>>(), bus.m1);
+        assert!(bus.reads.is_empty());
+        assert_eq!(None, z80.prefetched);
+    }
+
+    #[rstest]
+    fn test_prefetched_ignored_after_jump(mut z80: Z80) {
+        let mut mem = Ram::new();
+        mem[..2].copy_from_slice(&[0xdd, 0x04]);
+        mem[0x10] = 0x0c;
+        z80.step(&mut mem).unwrap();
+        assert_eq!(Some((0x0001, 0x04)), z80.prefetched);
+        z80.prog_counter = 0x0010;
+        let step = z80.step(&mut mem).unwrap();
+        assert_eq!(Instruction::INC_r(Register::C), step.instruction);
+    }
+
+    #[rstest]
+    fn test_reset(mut z80: Z80) {
+        z80.bc1 = 0x1234;
+        z80.prog_counter = 0x8000;
+        z80.refresh = 0x55;
+        z80.iff1 = true;
+        z80.interrupt_mode = InterruptMode::IM2;
+        z80.halted = true;
+        z80.cycles = 1000;
+        z80.reset();
+        assert_eq!(0xffff, z80.af);
+        assert_eq!(0xffff, z80.stack_ptr);
+        assert_eq!(0x0000, z80.bc1);
+        assert_eq!(0x0000, z80.prog_counter);
+        assert_eq!(0x00, z80.refresh);
+        assert!(!z80.iff1);
+        assert_eq!(InterruptMode::IM0, z80.interrupt_mode);
+        assert!(!z80.halted);
+        assert_eq!(1000, z80.cycles);
+    }
+
+    #[rstest]
+    fn test_soft_reset(mut z80: Z80) {
+        z80.af = 0x1234;
+        z80.stack_ptr = 0x8000;
+        z80.prog_counter = 0x4000;
+        z80.interrupt = 0x3f;
+        z80.refresh = 0x55;
+        z80.iff1 = true;
+        z80.iff2 = true;
+        z80.interrupt_mode = InterruptMode::IM1;
+        z80.soft_reset();
+        assert_eq!(0x1234, z80.af);
+        assert_eq!(0x8000, z80.stack_ptr);
+        assert_eq!(0x0000, z80.prog_counter);
+        assert_eq!(0x00, z80.interrupt);
+        assert_eq!(0x00, z80.refresh);
+        assert!(!z80.iff1 && !z80.iff2);
+        assert_eq!(InterruptMode::IM0, z80.interrupt_mode);
+    }
+
+    #[rstest]
+    fn test_display(mut z80: Z80) {
+        z80.set_reg(Register::AF1, 0xff00);
+        z80.set_reg(Register::HL1, 0xbeef);
+        z80.set_reg(Register::PC, 0x0038);
+        z80.set_reg(Register::I, 0x3f);
+        z80.set_reg(Register::R, 0x7f);
+        z80.set_reg(Register::F, 0x42);
+        z80.interrupt_mode = InterruptMode::IM1;
+        z80.iff1 = true;
+        z80.iff2 = true;
+        let dump = z80.to_string();
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(
+            vec![
+                "AF=0042 BC=0000 DE=0000 HL=0000 IX=0000 IY=0000",
+                "AF'=FF00 BC'=0000 DE'=0000 HL'=BEEF SP=0000 PC=0038",
+                "I=3F R=7F IM=1 IFF1=1 IFF2=1 F=-Z----N-",
+            ],
+            lines
+        );
+    }
+
+    #[rstest]
+    #[case::c(Flag::C, 0x01)]
+    #[case::n(Flag::N, 0x02)]
+    #[case::pv(Flag::PV, 0x04)]
+    #[case::x(Flag::X, 0x08)]
+    #[case::h(Flag::H, 0x10)]
+    #[case::y(Flag::Y, 0x20)]
+    #[case::z(Flag::Z, 0x40)]
+    #[case::s(Flag::S, 0x80)]
+    fn test_set_flag(mut z80: Z80, #[case] flag: Flag, #[case] mask: u8) {
+        z80.af = 0xab00;
+        z80.set_flag(flag, true);
+        assert_eq!(0xab00 | mask as u16, z80.af);
+        assert!(z80.flag(flag));
+        z80.af = 0xabff;
+        z80.set_flag(flag, false);
+        assert_eq!(0xabff & !(mask as u16), z80.af);
+        assert!(!z80.flag(flag));
+    }
+
+    #[rstest]
+    fn test_flags_bulk(mut z80: Z80) {
+        z80.af = 0x12d7;
+        assert_eq!(Flags::from_bits(0xd7), z80.flags());
+        z80.set_flags(Flags::from_bits(0x28));
+        assert_eq!(0x1228, z80.af);
+        assert!(z80.flag(Flag::X) && z80.flag(Flag::Y));
+    }
+
+    #[rstest]
+    #[case::nz(Condition::NZ, Flag::Z, false)]
+    #[case::z(Condition::Z, Flag::Z, true)]
+    #[case::nc(Condition::NC, Flag::C, false)]
+    #[case::c(Condition::C, Flag::C, true)]
+    #[case::po(Condition::PO, Flag::PV, false)]
+    #[case::pe(Condition::PE, Flag::PV, true)]
+    #[case::p(Condition::P, Flag::S, false)]
+    #[case::m(Condition::M, Flag::S, true)]
+    fn test_condition(mut z80: Z80, #[case] cc: Condition, #[case] flag: Flag, #[case] when: bool) {
+        for f in 0..=0xff {
+            z80.set_flags(Flags::from_bits(f));
+            assert_eq!(z80.flag(flag) == when, z80.condition(cc), "F = {:#04x}", f);
+        }
+    }
+
     #[rstest]
     fn test_fetch(mut z80: Z80) {
         z80.prog_counter = 2;
-        let mem = vec![0x12, 0x34, 0x56, 0x78, 0x9a];
-        let s = z80.fetch(&mem);
-        assert_eq!(0x56, s[0]);
-        assert_eq!(0x78, s[1]);
-        assert_eq!(0x9a, s[2]);
+        let mut mem = Ram::new();
+        mem[..5].copy_from_slice(&[0x12, 0x34, 0x56, 0x78, 0x9a]);
+        assert_eq!(
+            Ok((Instruction::LD_r_HL(Register::D), 1)),
+            z80.fetch(&mut mem)
+        );
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+    use crate::bus::Ram;
+    use rstest::*;
+
+    /// Enables interrupts, then increments 16 bytes at `0x4000` and halts.
+    const PROGRAM: [u8; 16] = [
+        0x31, 0x00, 0x80, // LD SP,0x8000
+        0xed, 0x56, // IM 1
+        0xfb, // EI
+        0x06, 0x10, // LD B,0x10
+        0x21, 0x00, 0x40, // LD HL,0x4000
+        0x34, // INC (HL)
+        0x23, // INC HL
+        0x10, 0xfc, // DJNZ -4
+        0x76, // HALT
+    ];
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[fixture]
+    fn mem() -> Ram {
+        let mut mem = Ram::new();
+        mem[..PROGRAM.len()].copy_from_slice(&PROGRAM);
+        mem
+    }
+
+    /// Run `saved` and `restored` to the end of the program and check they agree.
+    fn finish(mut saved: Z80, mut mem: Ram, mut restored: Z80) {
+        let mut mem2 = Ram::new();
+        mem2.copy_from_slice(&mem);
+        assert_eq!(saved, restored);
+        saved.run_until(&mut mem, |_| false);
+        restored.run_until(&mut mem2, |_| false);
+        assert_eq!(saved, restored);
+        assert_eq!(&mem[..], &mem2[..]);
+        assert_eq!(&[0x01; 16], &mem2[0x4000..0x4010]);
+    }
+
+    #[rstest]
+    fn test_json_round_trip(mut z80: Z80, mut mem: Ram) {
+        for _ in 0..3 {
+            z80.step(&mut mem).unwrap();
+        }
+        assert!(z80.ei_pending && z80.iff1);
+        let json = serde_json::to_string(&z80).unwrap();
+        for field in [
+            "iff1",
+            "iff2",
+            "interrupt_mode",
+            "halted",
+            "ei_pending",
+            "memptr",
+            "cycles",
+        ] {
+            assert!(json.contains(field), "{field} missing from {json}");
+        }
+        let restored: Z80 = serde_json::from_str(&json).unwrap();
+        finish(z80, mem, restored);
+    }
+
+    #[rstest]
+    fn test_binary_round_trip(mut z80: Z80, mut mem: Ram) {
+        z80.run_for_cycles(&mut mem, 100);
+        assert_eq!(InterruptMode::IM1, z80.interrupt_mode);
+        let bytes = bincode::serialize(&z80).unwrap();
+        let restored: Z80 = bincode::deserialize(&bytes).unwrap();
+        finish(z80, mem, restored);
+    }
+
+    #[rstest]
+    #[case::ld_r_ix(Instruction::LD_r_IX(Register::A, -3))]
+    #[case::jp_cc(Instruction::JP_cc_nn(Condition::PE, 0x1234))]
+    #[case::nop(Instruction::NOP)]
+    fn test_instruction_round_trip(#[case] inst: Instruction) {
+        let json = serde_json::to_string(&inst).unwrap();
+        assert_eq!(inst, serde_json::from_str::<Instruction>(&json).unwrap());
+        let bytes = bincode::serialize(&inst).unwrap();
+        assert_eq!(inst, bincode::deserialize::<Instruction>(&bytes).unwrap());
+    }
+
+    #[rstest]
+    fn test_flag_round_trip() {
+        for flag in Flag::ALL {
+            let json = serde_json::to_string(&flag).unwrap();
+            assert_eq!(flag, serde_json::from_str::<Flag>(&json).unwrap());
+        }
     }
 }
 
 /// Enums for identifying specific registers in other methods.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Register {
     /// Accumulator register
     A,
     /// Flag register
     F,
+    /// AF register pair
+    AF,
     /// B register
     B,
     /// C register
@@ -243,27 +889,52 @@ pub enum Register {
     L,
     /// HL register pair
     HL,
+    /// Index register X
+    IX,
+    /// High byte of index register X (undocumented)
+    IXH,
+    /// Low byte of index register X (undocumented)
+    IXL,
+    /// Index register Y
+    IY,
+    /// High byte of index register Y (undocumented)
+    IYH,
+    /// Low byte of index register Y (undocumented)
+    IYL,
+    /// Stack pointer
+    SP,
+    /// Program counter
+    PC,
+    /// Interrupt vector register
+    I,
+    /// Memory refresh register
+    R,
+    /// Alternate AF register pair
+    AF1,
+    /// Alternate BC register pair
+    BC1,
+    /// Alternate DE register pair
+    DE1,
+    /// Alternate HL register pair
+    HL1,
 }
 
-/// Enums for identifying different status flags.
-#[derive(Clone, Copy, Debug)]
-pub enum Flag {
-    /// Carry flag
-    C = 0,
-    /// Add/subtract flag
-    N = 1,
-    /// Parity/overflow flag
-    PV = 2,
-    /// Half-carry flag
-    H = 4,
-    /// Zero flag
-    Z = 6,
-    /// Sign flag
-    S = 7,
+/// Enums for identifying the maskable interrupt modes set by `IM n`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InterruptMode {
+    /// Execute the instruction placed on the data bus
+    #[default]
+    IM0,
+    /// Restart at address `0x0038`
+    IM1,
+    /// Call the address read from the vector table at `I * 256 + data bus`
+    IM2,
 }
 
 /// Enums for identifying different jump conditions.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Condition {
     /// Non-zero
     NZ,
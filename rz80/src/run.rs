@@ -0,0 +1,289 @@
+//! Methods for driving the CPU one instruction at a time or in bounded runs.
+use super::{bus::Bus, DecodeError, Instruction, Z80};
+use std::fmt;
+
+/// Record of a single instruction run by [`Z80::step`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Step {
+    /// Address the instruction was fetched from
+    pub address: u16,
+    /// The instruction that was executed
+    pub instruction: Instruction,
+    /// T-states taken, including accepting an interrupt afterwards
+    pub cycles: u32,
+}
+
+/// Reason that a bounded run of the CPU stopped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    /// The T-state budget given to [`Z80::run_for_cycles`] was used up
+    BudgetExhausted,
+    /// The CPU executed `HALT` and is waiting for an interrupt
+    Halted,
+    /// The predicate given to [`Z80::run_until`] was satisfied
+    Breakpoint,
+    /// An instruction could not be decoded
+    Error(DecodeError),
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopReason::BudgetExhausted => write!(f, "cycle budget exhausted"),
+            StopReason::Halted => write!(f, "halted"),
+            StopReason::Breakpoint => write!(f, "breakpoint"),
+            StopReason::Error(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<DecodeError> for StopReason {
+    fn from(e: DecodeError) -> Self {
+        StopReason::Error(e)
+    }
+}
+
+impl Z80 {
+    /// Fetch, decode and execute one instruction, then accept any pending interrupt.
+    ///
+    /// Returns what ran, or the reason the instruction could not be decoded. The CPU
+    /// is not paced to real time: the caller should use the T-states reported, or
+    /// [`Z80::cycles`], to do so.
+    ///
+    /// # Arguments
+    /// - `bus`: the memory and I/O ports available to the CPU
+    ///
+    /// # Example
+    /// ```
+    /// # use rz80::{bus::Ram, Instruction, Register, Z80};
+    /// let mut cpu: Z80 = Default::default();
+    /// let mut bus = Ram::new();
+    /// bus[..3].copy_from_slice(&[0x3e, 0x12, 0x76]); // LD A,0x12; HALT
+    /// let step = cpu.step(&mut bus).unwrap();
+    /// assert_eq!(0x0000, step.address);
+    /// assert_eq!(Instruction::LD_r_n(Register::A, 0x12), step.instruction);
+    /// assert_eq!(7, step.cycles);
+    /// assert_eq!(4, cpu.step(&mut bus).unwrap().cycles);
+    /// assert_eq!(11, cpu.cycles);
+    /// ```
+    pub fn step(&mut self, bus: &mut impl Bus) -> Result<Step, DecodeError> {
+        let address = self.prog_counter;
+        let (instruction, width) = self.fetch(bus)?;
+        self.prog_counter = self.prog_counter.wrapping_add(width as u16);
+        let cycles = self.execute(instruction, bus) + self.accept_interrupt(bus).unwrap_or(0);
+        Ok(Step {
+            address,
+            instruction,
+            cycles,
+        })
+    }
+
+    /// Run instructions until at least `budget` T-states have elapsed.
+    ///
+    /// The last instruction is always completed, so the run may overshoot the budget
+    /// by a few T-states. The run also stops when the CPU executes `HALT`; if it was
+    /// already halted it keeps executing `HALT` until the budget is used up or an
+    /// interrupt arrives.
+    ///
+    /// # Arguments
+    /// - `bus`: the memory and I/O ports available to the CPU
+    /// - `budget`: number of T-states to run for
+    ///
+    /// # Example
+    /// ```
+    /// # use rz80::{bus::Ram, StopReason, Z80};
+    /// let mut cpu: Z80 = Default::default();
+    /// let mut bus = Ram::new();
+    /// assert_eq!(StopReason::BudgetExhausted, cpu.run_for_cycles(&mut bus, 10));
+    /// assert_eq!(12, cpu.cycles);
+    /// ```
+    pub fn run_for_cycles(&mut self, bus: &mut impl Bus, budget: u64) -> StopReason {
+        let end = self.cycles.saturating_add(budget);
+        while self.cycles < end {
+            let was_halted = self.halted;
+            if let Err(e) = self.step(bus) {
+                return e.into();
+            }
+            if self.halted && !was_halted {
+                return StopReason::Halted;
+            }
+        }
+        StopReason::BudgetExhausted
+    }
+
+    /// Run instructions until `predicate` holds.
+    ///
+    /// The predicate is checked after every instruction, so a breakpoint on an address
+    /// stops the CPU before the instruction at that address runs. The run also stops
+    /// when the CPU executes `HALT`.
+    ///
+    /// # Arguments
+    /// - `bus`: the memory and I/O ports available to the CPU
+    /// - `predicate`: condition on the CPU state at which to stop
+    ///
+    /// # Example
+    /// ```
+    /// # use rz80::{bus::Ram, StopReason, Z80};
+    /// let mut cpu: Z80 = Default::default();
+    /// let mut bus = Ram::new();
+    /// let reason = cpu.run_until(&mut bus, |cpu| cpu.prog_counter == 0x0003);
+    /// assert_eq!(StopReason::Breakpoint, reason);
+    /// assert_eq!(12, cpu.cycles);
+    /// ```
+    pub fn run_until(
+        &mut self,
+        bus: &mut impl Bus,
+        mut predicate: impl FnMut(&Z80) -> bool,
+    ) -> StopReason {
+        loop {
+            let was_halted = self.halted;
+            if let Err(e) = self.step(bus) {
+                return e.into();
+            }
+            if predicate(self) {
+                return StopReason::Breakpoint;
+            }
+            if self.halted && !was_halted {
+                return StopReason::Halted;
+            }
+        }
+    }
+
+    /// Start the cpu running the fetch-decode-execute cycle.
+    ///
+    /// This method loops infinitely, as fast as the host allows, unless an instruction
+    /// cannot be decoded, in which case it returns the reason.
+    ///
+    /// # Arguments
+    /// - `bus`: the memory and I/O ports available to the CPU
+    pub fn run(&mut self, bus: &mut impl Bus) -> Result<(), DecodeError> {
+        loop {
+            self.step(bus)?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod run_tests {
+    use super::*;
+    use crate::{bus::Ram, InterruptMode, Register};
+    use rstest::*;
+
+    #[fixture]
+    fn z80() -> Z80 {
+        Default::default()
+    }
+
+    #[fixture]
+    fn mem() -> Ram {
+        Ram::new()
+    }
+
+    #[rstest]
+    fn test_step_includes_interrupt(mut z80: Z80, mut mem: Ram) {
+        z80.stack_ptr = 0x8000;
+        z80.iff1 = true;
+        z80.interrupt_mode = InterruptMode::IM1;
+        z80.assert_int(0xff);
+        let step = z80.step(&mut mem).unwrap();
+        assert_eq!(Instruction::NOP, step.instruction);
+        assert_eq!(4 + 13, step.cycles);
+        assert_eq!(0x0038, z80.prog_counter);
+        assert_eq!(17, z80.cycles);
+    }
+
+    #[rstest]
+    fn test_step_undefined_ed(mut z80: Z80, mut mem: Ram) {
+        mem[0x1234..0x1236].copy_from_slice(&[0xed, 0x77]);
+        z80.prog_counter = 0x1234;
+        let step = z80.step(&mut mem).unwrap();
+        assert_eq!(Instruction::ED_NOP(0x77), step.instruction);
+        assert_eq!(8, step.cycles);
+        assert_eq!(0x1236, z80.prog_counter);
+        assert_eq!(2, z80.refresh);
+    }
+
+    #[rstest]
+    fn test_run_for_cycles_budget(mut z80: Z80, mut mem: Ram) {
+        // LD B,0; DJNZ -2 loops 256 times
+        mem[..4].copy_from_slice(&[0x06, 0x00, 0x10, 0xfe]);
+        assert_eq!(
+            StopReason::BudgetExhausted,
+            z80.run_for_cycles(&mut mem, 20)
+        );
+        assert_eq!(7 + 13, z80.cycles);
+        assert_eq!(StopReason::BudgetExhausted, z80.run_for_cycles(&mut mem, 1));
+        assert_eq!(7 + 13 + 13, z80.cycles);
+        assert_eq!(0xfe, z80.reg(Register::B));
+    }
+
+    #[rstest]
+    fn test_run_for_cycles_halt(mut z80: Z80, mut mem: Ram) {
+        mem[..2].copy_from_slice(&[0x00, 0x76]);
+        assert_eq!(StopReason::Halted, z80.run_for_cycles(&mut mem, 1000));
+        assert_eq!(8, z80.cycles);
+        assert_eq!(
+            StopReason::BudgetExhausted,
+            z80.run_for_cycles(&mut mem, 100)
+        );
+        assert_eq!(108, z80.cycles);
+        assert!(z80.halted);
+    }
+
+    #[rstest]
+    #[case::from_start(0)]
+    #[case::part_way(1_000)]
+    fn test_run_for_cycles_unbounded(mut z80: Z80, mut mem: Ram, #[case] start: u64) {
+        z80.cycles = start;
+        mem[..2].copy_from_slice(&[0x00, 0x76]);
+        assert_eq!(StopReason::Halted, z80.run_for_cycles(&mut mem, u64::MAX));
+        assert_eq!(start + 8, z80.cycles);
+    }
+
+    #[rstest]
+    fn test_step_im0_cycles(mut z80: Z80, mut mem: Ram) {
+        // RST 0x38 supplied by the device in IM 0
+        z80.stack_ptr = 0x8000;
+        z80.iff1 = true;
+        z80.cycles = u64::from(u32::MAX) + 1;
+        z80.assert_int(0xff);
+        let step = z80.step(&mut mem).unwrap();
+        assert_eq!(4 + 11 + 2, step.cycles);
+        assert_eq!(0x0038, z80.prog_counter);
+    }
+
+    #[rstest]
+    fn test_run_until_breakpoint(mut z80: Z80, mut mem: Ram) {
+        // CALL 0x0010; ... 0x0010: RET
+        mem[..3].copy_from_slice(&[0xcd, 0x10, 0x00]);
+        mem[0x10] = 0xc9;
+        z80.stack_ptr = 0x8000;
+        let reason = z80.run_until(&mut mem, |cpu| cpu.prog_counter == 0x0003);
+        assert_eq!(StopReason::Breakpoint, reason);
+        assert_eq!(17 + 10, z80.cycles);
+    }
+
+    #[rstest]
+    fn test_run_until_past_undefined_ed(mut z80: Z80, mut mem: Ram) {
+        // ED 77 (no-op); LD A,0x12; HALT
+        mem[..5].copy_from_slice(&[0xed, 0x77, 0x3e, 0x12, 0x76]);
+        assert_eq!(StopReason::Halted, z80.run_until(&mut mem, |_| false));
+        assert_eq!(0x12, z80.reg(Register::A));
+        assert_eq!(8 + 7 + 4, z80.cycles);
+    }
+
+    #[rstest]
+    fn test_run_for_cycles_past_undefined_ed(mut z80: Z80, mut mem: Ram) {
+        mem[..5].copy_from_slice(&[0xed, 0x77, 0x3e, 0x12, 0x76]);
+        assert_eq!(StopReason::Halted, z80.run_for_cycles(&mut mem, 100));
+        assert_eq!(0x12, z80.reg(Register::A));
+        assert_eq!(0x0004, z80.prog_counter);
+        assert_eq!(8 + 7 + 4, z80.cycles);
+    }
+
+    #[rstest]
+    fn test_run_until_halt(mut z80: Z80, mut mem: Ram) {
+        mem[0] = 0x76;
+        assert_eq!(StopReason::Halted, z80.run_until(&mut mem, |_| false));
+    }
+}